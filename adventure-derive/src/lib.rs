@@ -0,0 +1,266 @@
+//! Derive macros to implement the traits of [`adventure`] for request types.
+//!
+//! The macros are configured with `#[adventure(...)]` attributes:
+//!
+//!  - `ok = T` and `error = E` for the results of the request,
+//!  - `client = C` for the type of the client,
+//!  - `response = R` for the type of the response,
+//!  - `send = path` for a function `fn(&Self, C) -> R`, which sends the
+//!    request to the client,
+//!  - `cursor = field` for a field of `Option` in both of the request and
+//!    its successful result, which points the next page.
+//!
+//! Examples
+//! --------
+//!
+//! ```ignore
+//! use adventure::prelude::*;
+//! use adventure::response::LocalFutureResponseObj;
+//!
+//! #[derive(BaseRequest, Request, PagedRequest)]
+//! #[adventure(ok = ListUsersResponse, error = Error)]
+//! #[adventure(
+//!     client = &'a Client,
+//!     response = LocalFutureResponseObj<'a, ListUsersResponse, Error>,
+//!     send = list_users,
+//!     cursor = next_token,
+//! )]
+//! struct ListUsers {
+//!     next_token: Option<String>,
+//! }
+//!
+//! fn list_users<'a>(
+//!     req: &ListUsers,
+//!     client: &'a Client,
+//! ) -> LocalFutureResponseObj<'a, ListUsersResponse, Error> {
+//!     // ...
+//! }
+//! ```
+//!
+//! [`adventure`]: https://docs.rs/adventure
+#![deny(rust_2018_idioms)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_macro_input, Attribute, DeriveInput, Error, GenericParam, Generics, Ident, Lifetime,
+    LifetimeDef, Path, Result, Token, Type,
+};
+
+/// Implements `BaseRequest` with `ok` and `error` attributes.
+#[proc_macro_derive(BaseRequest, attributes(adventure))]
+pub fn derive_base_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(base_request(&input))
+}
+
+/// Implements `Request` and `OneshotRequest` with `client`, `response`, and
+/// `send` attributes.
+#[proc_macro_derive(Request, attributes(adventure))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(request(&input))
+}
+
+/// Implements `PagedRequest` with `cursor` attribute.
+#[proc_macro_derive(PagedRequest, attributes(adventure))]
+pub fn derive_paged_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(paged_request(&input))
+}
+
+fn expand(result: Result<TokenStream2>) -> TokenStream {
+    result.unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn base_request(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = Attrs::from_attrs(&input.attrs)?;
+    let ok = require(attrs.ok.as_ref(), "ok")?;
+    let error = require(attrs.error.as_ref(), "error")?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::adventure::BaseRequest for #name #ty_generics #where_clause {
+            type Ok = #ok;
+            type Error = #error;
+        }
+    })
+}
+
+fn request(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = Attrs::from_attrs(&input.attrs)?;
+    let client = require(attrs.client.as_ref(), "client")?;
+    let response = require(attrs.response.as_ref(), "response")?;
+    let send = require(attrs.send.as_ref(), "send")?;
+
+    let name = &input.ident;
+    let generics = with_lifetimes(&input.generics, &[client, response]);
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::adventure::OneshotRequest<#client> for #name #ty_generics
+        #where_clause
+        {
+            type Response = #response;
+
+            fn send_once(self, client: #client) -> Self::Response {
+                #send(&self, client)
+            }
+        }
+
+        impl #impl_generics ::adventure::Request<#client> for #name #ty_generics
+        #where_clause
+        {
+            type Response = #response;
+
+            fn send(self: ::core::pin::Pin<&mut Self>, client: #client) -> Self::Response {
+                #send(&*self, client)
+            }
+        }
+    })
+}
+
+fn paged_request(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = Attrs::from_attrs(&input.attrs)?;
+    let cursor = require(attrs.cursor.as_ref(), "cursor")?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::adventure::PagedRequest for #name #ty_generics #where_clause {
+            fn advance(&mut self, response: &Self::Ok) -> bool {
+                if let ::core::option::Option::Some(next) = &response.#cursor {
+                    self.#cursor = ::core::option::Option::Some(::core::clone::Clone::clone(next));
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    })
+}
+
+/// Adds lifetimes used in the given types, which are not declared yet.
+fn with_lifetimes(generics: &Generics, types: &[&Type]) -> Generics {
+    let mut visitor = Lifetimes::default();
+    for ty in types {
+        visitor.visit_type(ty);
+    }
+
+    let mut generics = generics.clone();
+    for lifetime in visitor.lifetimes.into_iter().rev() {
+        let declared = generics.lifetimes().any(|def| def.lifetime == lifetime);
+        if !declared && lifetime.ident != "static" && lifetime.ident != "_" {
+            let def = LifetimeDef::new(lifetime);
+            generics.params.insert(0, GenericParam::Lifetime(def));
+        }
+    }
+    generics
+}
+
+#[derive(Default)]
+struct Lifetimes {
+    lifetimes: Vec<Lifetime>,
+}
+
+impl<'ast> Visit<'ast> for Lifetimes {
+    fn visit_lifetime(&mut self, lifetime: &'ast Lifetime) {
+        if !self.lifetimes.contains(lifetime) {
+            self.lifetimes.push(lifetime.clone());
+        }
+        visit::visit_lifetime(self, lifetime);
+    }
+}
+
+fn require<'a, T>(value: Option<&'a T>, key: &str) -> Result<&'a T> {
+    value.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            format!("missing `{}` in `#[adventure(...)]` attribute", key),
+        )
+    })
+}
+
+#[derive(Default)]
+struct Attrs {
+    ok: Option<Type>,
+    error: Option<Type>,
+    client: Option<Type>,
+    response: Option<Type>,
+    send: Option<Path>,
+    cursor: Option<Ident>,
+}
+
+impl Attrs {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut result = Attrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("adventure")) {
+            let args = attr.parse_args_with(Punctuated::<Arg, Token![,]>::parse_terminated)?;
+            for arg in args {
+                result.set(arg)?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn set(&mut self, arg: Arg) -> Result<()> {
+        let key = arg.key;
+        let duplicated = match arg.value {
+            Value::Ok(ty) => self.ok.replace(ty).is_some(),
+            Value::Error(ty) => self.error.replace(ty).is_some(),
+            Value::Client(ty) => self.client.replace(ty).is_some(),
+            Value::Response(ty) => self.response.replace(ty).is_some(),
+            Value::Send(path) => self.send.replace(path).is_some(),
+            Value::Cursor(ident) => self.cursor.replace(ident).is_some(),
+        };
+        if duplicated {
+            Err(Error::new(
+                key.span(),
+                format!("duplicated `{}` attribute", key),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct Arg {
+    key: Ident,
+    value: Value,
+}
+
+enum Value {
+    Ok(Type),
+    Error(Type),
+    Client(Type),
+    Response(Type),
+    Send(Path),
+    Cursor(Ident),
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let key: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = match key.to_string().as_str() {
+            "ok" => Value::Ok(input.parse()?),
+            "error" => Value::Error(input.parse()?),
+            "client" => Value::Client(input.parse()?),
+            "response" => Value::Response(input.parse()?),
+            "send" => Value::Send(input.parse()?),
+            "cursor" => Value::Cursor(input.parse()?),
+            _ => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("unknown `{}` attribute", key),
+                ))
+            }
+        };
+        Ok(Arg { key, value })
+    }
+}
@@ -0,0 +1,149 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::{pin_mut, prelude::*};
+
+use adventure::and_then::AndThenError;
+use adventure::prelude::*;
+use adventure::response::*;
+
+use crate::fixture::Client;
+
+/// A client whose queues are unavailable for the given number of messages.
+#[derive(Default)]
+struct Queues {
+    client: Client,
+    unavailable: AtomicUsize,
+}
+
+type Resp<T> = FutureResponseObj<'static, T, String>;
+
+#[derive(Clone)]
+struct GetQueueUrl {
+    name: &'static str,
+}
+
+impl BaseRequest for GetQueueUrl {
+    type Ok = String;
+    type Error = String;
+}
+
+impl Request<&Queues> for GetQueueUrl {
+    type Response = Resp<String>;
+
+    fn send(self: Pin<&mut Self>, queues: &Queues) -> Self::Response {
+        let url = format!("https://queue/{}", self.name);
+        Resp::new(future::ready(queues.client.call().map(|()| url)))
+    }
+}
+
+impl RetriableRequest for GetQueueUrl {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        true
+    }
+}
+
+#[derive(Clone)]
+struct SendMessage {
+    url: String,
+}
+
+impl BaseRequest for SendMessage {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl OneshotRequest<&Queues> for SendMessage {
+    type Response = Resp<usize>;
+
+    fn send_once(self, queues: &Queues) -> Self::Response {
+        queues.client.called.fetch_add(1, Ordering::SeqCst);
+        if self.url.ends_with("dead") {
+            Resp::new(future::err("no such queue".to_owned()))
+        } else if queues.unavailable.load(Ordering::SeqCst) > 0 {
+            queues.unavailable.fetch_sub(1, Ordering::SeqCst);
+            Resp::new(future::err("unavailable".to_owned()))
+        } else {
+            Resp::new(future::ok(self.url.len()))
+        }
+    }
+}
+
+impl RetriableRequest for SendMessage {
+    fn should_retry(&self, error: &Self::Error, _next_interval: Duration) -> bool {
+        error == "unavailable"
+    }
+}
+
+#[tokio::test]
+async fn and_then_send() {
+    let queues = Queues::default();
+    let req = GetQueueUrl { name: "jobs" }.and_then(|url| SendMessage { url });
+    pin_mut!(req);
+
+    assert_eq!(req.as_mut().send(&queues).await.unwrap(), 18);
+    assert_eq!(req.send(&queues).await.unwrap(), 18);
+    assert_eq!(queues.client.called.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn and_then_errors() {
+    let queues = Queues {
+        client: Client {
+            fail_until: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let req = GetQueueUrl { name: "dead" }.and_then(|url| SendMessage { url });
+    pin_mut!(req);
+
+    match req.as_mut().send(&queues).await {
+        Err(AndThenError::First(e)) => assert_eq!(e, "0 failed"),
+        _ => panic!("the first request should fail"),
+    }
+    match req.send(&queues).await {
+        Err(AndThenError::Second(e)) => assert_eq!(e, "no such queue"),
+        _ => panic!("the second request should fail"),
+    }
+    assert_eq!(queues.client.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn and_then_retry_first() {
+    let queues = Queues {
+        client: Client {
+            fail_until: 3,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let req = GetQueueUrl { name: "jobs" }
+        .and_then(|url| SendMessage { url })
+        .retry();
+
+    assert_eq!(req.send_once(&queues).await.unwrap(), 18);
+    assert_eq!(queues.client.called.load(Ordering::SeqCst), 5);
+}
+
+#[tokio::test]
+async fn and_then_retry_second() {
+    let queues = Queues {
+        unavailable: AtomicUsize::new(1),
+        ..Default::default()
+    };
+    let built = AtomicUsize::new(0);
+    let req = GetQueueUrl { name: "jobs" }
+        .and_then(|url| {
+            built.fetch_add(1, Ordering::SeqCst);
+            SendMessage { url }
+        })
+        .retry();
+
+    assert_eq!(req.send_once(&queues).await.unwrap(), 18);
+    // the first request is sent only once, and the second one twice
+    assert_eq!(queues.client.called.load(Ordering::SeqCst), 3);
+    // the second request is built only once
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+}
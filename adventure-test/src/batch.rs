@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::prelude::*;
+use tokio::time::delay_for;
+
+use adventure::batch::send_all;
+use adventure::prelude::*;
+use adventure::response::*;
+
+#[derive(Default)]
+struct Client {
+    sent: AtomicUsize,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+type Resp<'a> = FutureResponseObj<'a, usize, String>;
+
+struct GetItem {
+    id: usize,
+    delay_ms: u64,
+}
+
+impl BaseRequest for GetItem {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl<'a> OneshotRequest<&'a Client> for GetItem {
+    type Response = Resp<'a>;
+
+    fn send_once(self, client: &'a Client) -> Self::Response {
+        client.sent.fetch_add(1, Ordering::SeqCst);
+        let n = client.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        if n > client.max_in_flight.load(Ordering::SeqCst) {
+            client.max_in_flight.store(n, Ordering::SeqCst);
+        }
+        let GetItem { id, delay_ms } = self;
+        Resp::new(async move {
+            delay_for(Duration::from_millis(delay_ms)).await;
+            client.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if id % 4 == 3 {
+                Err(format!("{} failed", id))
+            } else {
+                Ok(id * 10)
+            }
+        })
+    }
+}
+
+fn requests(n: usize) -> impl Iterator<Item = GetItem> {
+    (0..n).map(move |id| GetItem {
+        id,
+        delay_ms: 10 * (n - id) as u64,
+    })
+}
+
+#[tokio::test]
+async fn send_all_unordered() {
+    let client = Client::default();
+    let results: Vec<_> = send_all(requests(3), &client).collect().await;
+
+    assert_eq!(results, vec![(2, Ok(20)), (1, Ok(10)), (0, Ok(0))]);
+    assert_eq!(client.max_in_flight.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn send_all_ordered_with_concurrency() {
+    let client = Client::default();
+    let results: Vec<_> = send_all(requests(5), &client)
+        .concurrency(2)
+        .ordered()
+        .collect()
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            (0, Ok(0)),
+            (1, Ok(10)),
+            (2, Ok(20)),
+            (3, Err("3 failed".to_owned())),
+            (4, Ok(40)),
+        ]
+    );
+    assert_eq!(client.max_in_flight.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn send_all_fail_fast() {
+    let client = Client::default();
+    let results: Vec<_> = send_all(requests(8), &client)
+        .concurrency(1)
+        .fail_fast()
+        .collect()
+        .await;
+
+    assert_eq!(
+        results,
+        vec![
+            (0, Ok(0)),
+            (1, Ok(10)),
+            (2, Ok(20)),
+            (3, Err("3 failed".to_owned())),
+        ]
+    );
+    assert_eq!(client.sent.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn send_all_ordered_fail_fast() {
+    let client = Client::default();
+    let results: Vec<_> = send_all(requests(8), &client)
+        .ordered()
+        .fail_fast()
+        .collect()
+        .await;
+
+    // the failure of #7 arrives first, but #3 is the earliest one
+    assert_eq!(
+        results,
+        vec![
+            (0, Ok(0)),
+            (1, Ok(10)),
+            (2, Ok(20)),
+            (3, Err("3 failed".to_owned())),
+        ]
+    );
+    assert_eq!(client.sent.load(Ordering::SeqCst), 8);
+}
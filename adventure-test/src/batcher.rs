@@ -0,0 +1,125 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::prelude::*;
+
+use adventure::batcher::{Batch, Batcher};
+use adventure::prelude::*;
+use adventure::response::*;
+use adventure::retry::TokioTimer;
+
+#[derive(Default)]
+struct Client {
+    batches: Mutex<Vec<Vec<usize>>>,
+}
+
+type Resp = FutureResponseObj<'static, Vec<(usize, String)>, String>;
+
+struct DescribeTasks {
+    ids: Vec<usize>,
+}
+
+impl BaseRequest for DescribeTasks {
+    type Ok = Vec<(usize, String)>;
+    type Error = String;
+}
+
+impl OneshotRequest<Arc<Client>> for DescribeTasks {
+    type Response = Resp;
+
+    fn send_once(self, client: Arc<Client>) -> Self::Response {
+        client.batches.lock().unwrap().push(self.ids.clone());
+        if self.ids.contains(&0) {
+            return Resp::new(future::err("invalid id".to_owned()));
+        }
+        // tasks of odd ids are not found.
+        let tasks = self
+            .ids
+            .into_iter()
+            .filter(|id| id % 2 == 0)
+            .map(|id| (id, format!("task-{}", id)))
+            .collect();
+        Resp::new(future::ok(tasks))
+    }
+}
+
+struct DescribeTask;
+
+impl Batch<Arc<Client>> for DescribeTask {
+    type Key = usize;
+    type Value = Option<String>;
+    type Error = String;
+    type Request = DescribeTasks;
+
+    fn batch(&self, keys: &[usize]) -> DescribeTasks {
+        DescribeTasks { ids: keys.to_vec() }
+    }
+
+    fn split(
+        &self,
+        keys: &[usize],
+        result: Result<Vec<(usize, String)>, String>,
+    ) -> Vec<Result<Option<String>, String>> {
+        match result {
+            Ok(tasks) => keys
+                .iter()
+                .map(|k| Ok(tasks.iter().find(|(id, _)| id == k).map(|t| t.1.clone())))
+                .collect(),
+            Err(e) => keys.iter().map(|_| Err(e.clone())).collect(),
+        }
+    }
+}
+
+#[tokio::test]
+async fn batcher_coalesces_loads() {
+    let client = Arc::new(Client::default());
+    let (batcher, driver) = Batcher::new(DescribeTask, client.clone(), TokioTimer);
+    let driver = tokio::spawn(driver);
+
+    let results = future::join_all((1..=5).map(|id| batcher.load(id))).await;
+    let results: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+    assert_eq!(
+        results,
+        vec![
+            None,
+            Some("task-2".to_owned()),
+            None,
+            Some("task-4".to_owned()),
+            None
+        ]
+    );
+    assert_eq!(*client.batches.lock().unwrap(), vec![vec![1, 2, 3, 4, 5]]);
+
+    let res = future::join(batcher.load(0), batcher.load(2)).await;
+    assert_eq!(res.0.unwrap_err().into_inner().unwrap(), "invalid id");
+    assert_eq!(res.1.unwrap_err().into_inner().unwrap(), "invalid id");
+
+    drop(batcher);
+    driver.await.unwrap();
+}
+
+#[tokio::test]
+async fn batcher_max_size() {
+    let client = Arc::new(Client::default());
+    let (batcher, driver) = Batcher::new(DescribeTask, client.clone(), TokioTimer);
+    let driver = driver.with_max_size(2).with_window(Duration::from_secs(10));
+
+    let loads = future::join_all((1..=5).map(|id| batcher.load(id)));
+    drop(batcher);
+    let (results, ()) = future::join(loads, driver).await;
+    assert_eq!(results.len(), 5);
+    assert_eq!(
+        *client.batches.lock().unwrap(),
+        vec![vec![1, 2], vec![3, 4], vec![5]]
+    );
+}
+
+#[tokio::test]
+async fn batcher_closed() {
+    let client = Arc::new(Client::default());
+    let (batcher, driver) = Batcher::new(DescribeTask, client, TokioTimer);
+    drop(driver);
+
+    let res = batcher.load(1).await;
+    assert!(res.unwrap_err().is_closed());
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use adventure::prelude::*;
+use adventure::retry::{ExponentialBackoff, ThreadTimer};
+
+use crate::fixture::{Client, ListItems};
+
+#[test]
+fn send_blocking() {
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let res = ListItems { page: 3 }.oneshot().send_blocking(&client);
+    assert_eq!(res.unwrap_err(), "0 failed");
+
+    let res = ListItems { page: 3 }.oneshot().send_blocking(&client);
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[test]
+fn paginate_blocking() {
+    let client = Client::default();
+    let pages: Result<Vec<_>, _> = ListItems { page: 0 }.paginate_blocking(&client).collect();
+    assert_eq!(pages.unwrap(), vec![0, 1, 2]);
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn retry_blocking() {
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let res = ListItems { page: 5 }
+        .retry_blocking()
+        .send_blocking(&client);
+    assert_eq!(res.unwrap(), 5);
+
+    let client = Client {
+        fail_until: 5,
+        ..Default::default()
+    };
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_millis(10);
+    let pages: Result<Vec<_>, _> = ListItems { page: 0 }
+        .retry_with_config(ThreadTimer, (), backoff)
+        .paginate_blocking(&client)
+        .collect();
+    assert_eq!(pages.unwrap(), vec![0, 1, 2]);
+    assert_eq!(client.called.load(Ordering::SeqCst), 8);
+}
@@ -0,0 +1,84 @@
+use std::sync::atomic::Ordering;
+
+use futures::prelude::*;
+
+use adventure::boxed::{BoxOneshotRequest, BoxRequest, LocalBoxOneshotRequest};
+use adventure::oneshot::Oneshot;
+use adventure::prelude::*;
+
+use crate::fixture::{backoff, Client, ListItems};
+
+type Boxed<'a> = BoxRequest<'a, &'a Client, usize, String>;
+
+#[tokio::test]
+async fn box_heterogeneous() {
+    let client = Client::default();
+    let requests: Vec<Boxed<'_>> = vec![
+        BoxRequest::new(ListItems { page: 1 }),
+        BoxRequest::new(ListItems { page: 2 }.map_ok(|n| n * 10)),
+        BoxRequest::new(
+            ListItems { page: 3 }
+                .retry_with_backoff(backoff())
+                .map_err(|e| e.into_inner().unwrap()),
+        ),
+    ];
+
+    let mut results = vec![];
+    for req in requests {
+        results.push(req.send_once(&client).await.unwrap());
+    }
+    assert_eq!(results, vec![1, 20, 3]);
+}
+
+#[tokio::test]
+async fn box_paginate() {
+    let client = Client::default();
+    let pages: Vec<_> = Boxed::paged(ListItems { page: 0 })
+        .paginate(&client)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![0, 1, 2]);
+
+    let pages: Vec<_> = Boxed::new(ListItems { page: 0 })
+        .paginate(&client)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![0]);
+}
+
+#[tokio::test]
+async fn box_retry() {
+    let client = Client {
+        fail_until: 2,
+        ..Default::default()
+    };
+    let res = Boxed::new(ListItems { page: 7 })
+        .retry_with_backoff(backoff())
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap_err().into_inner().unwrap(), "0 failed");
+
+    let res = Boxed::retriable(ListItems { page: 7 })
+        .retry_with_backoff(backoff())
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 7);
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn box_oneshot() {
+    let client = Client::default();
+    let requests: Vec<BoxOneshotRequest<'static, &Client, usize, String>> = vec![
+        BoxOneshotRequest::new(Oneshot::from(ListItems { page: 1 })),
+        BoxOneshotRequest::new(Oneshot::from(ListItems { page: 2 }).repeat()),
+    ];
+    for (i, req) in requests.into_iter().enumerate() {
+        assert_eq!(req.send_once(&client).await.unwrap(), i + 1);
+    }
+
+    let local = LocalBoxOneshotRequest::new(Oneshot::from(ListItems { page: 3 }));
+    assert_eq!(local.send_once(&client).await.unwrap(), 3);
+}
@@ -0,0 +1,139 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::sleep;
+use std::time::Duration;
+
+use futures::{executor::block_on, prelude::*};
+
+use adventure::breaker::{BreakerState, CircuitBreaker, Clock};
+use adventure::prelude::*;
+use adventure::response::*;
+
+use crate::fixture::ManualClock;
+
+#[derive(Default)]
+struct Client {
+    called: AtomicUsize,
+    healthy: AtomicBool,
+}
+
+type Resp = FutureResponseObj<'static, (), u16>;
+
+#[derive(Clone)]
+struct GetStatus {
+    bad_request: bool,
+}
+
+impl BaseRequest for GetStatus {
+    type Ok = ();
+    type Error = u16;
+}
+
+impl Request<&Client> for GetStatus {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        client.called.fetch_add(1, Ordering::SeqCst);
+        if self.bad_request {
+            Resp::new(future::err(400))
+        } else if client.healthy.load(Ordering::SeqCst) {
+            Resp::new(future::ok(()))
+        } else {
+            Resp::new(future::err(503))
+        }
+    }
+}
+
+fn send<K: Clock>(
+    req: GetStatus,
+    client: &Client,
+    breaker: &CircuitBreaker<K>,
+) -> Result<(), bool> {
+    let req = req
+        .with_breaker(breaker)
+        .with_predicate(|e: &u16| *e >= 500)
+        .oneshot();
+    block_on(req.send_once(client)).map_err(|e| e.is_open())
+}
+
+#[test]
+fn breaker_opens_and_recovers() {
+    let client = Client::default();
+    let breaker = CircuitBreaker::new()
+        .with_consecutive_failures(3)
+        .with_cool_down(Duration::from_millis(50));
+    let req = GetStatus { bad_request: false };
+
+    for _ in 0..3 {
+        assert_eq!(send(req.clone(), &client, &breaker), Err(false));
+    }
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert_eq!(send(req.clone(), &client, &breaker), Err(true));
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+
+    sleep(Duration::from_millis(60));
+    assert_eq!(breaker.state(), BreakerState::HalfOpen);
+    assert_eq!(send(req.clone(), &client, &breaker), Err(false));
+    assert_eq!(breaker.state(), BreakerState::Open);
+
+    sleep(Duration::from_millis(60));
+    client.healthy.store(true, Ordering::SeqCst);
+    assert_eq!(send(req, &client, &breaker), Ok(()));
+    assert_eq!(breaker.state(), BreakerState::Closed);
+    assert_eq!(client.called.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn breaker_ignores_client_errors() {
+    let client = Client::default();
+    let breaker = CircuitBreaker::new().with_consecutive_failures(2);
+
+    for _ in 0..5 {
+        assert_eq!(
+            send(GetStatus { bad_request: true }, &client, &breaker),
+            Err(false)
+        );
+    }
+    assert_eq!(breaker.state(), BreakerState::Closed);
+}
+
+#[test]
+fn breaker_failure_ratio() {
+    let client = Client::default();
+    let breaker = CircuitBreaker::new()
+        .with_consecutive_failures(0)
+        .with_failure_ratio(0.5, 4);
+
+    client.healthy.store(true, Ordering::SeqCst);
+    send(GetStatus { bad_request: false }, &client, &breaker).unwrap();
+    client.healthy.store(false, Ordering::SeqCst);
+    send(GetStatus { bad_request: false }, &client, &breaker).unwrap_err();
+    client.healthy.store(true, Ordering::SeqCst);
+    send(GetStatus { bad_request: false }, &client, &breaker).unwrap();
+    assert_eq!(breaker.state(), BreakerState::Closed);
+
+    client.healthy.store(false, Ordering::SeqCst);
+    send(GetStatus { bad_request: false }, &client, &breaker).unwrap_err();
+    assert_eq!(breaker.state(), BreakerState::Open);
+}
+
+#[test]
+fn breaker_with_clock() {
+    let client = Client::default();
+    let clock = ManualClock::default();
+    let breaker = CircuitBreaker::with_clock(clock.clone())
+        .with_consecutive_failures(1)
+        .with_cool_down(Duration::from_secs(60));
+    let req = GetStatus { bad_request: false };
+
+    assert_eq!(send(req.clone(), &client, &breaker), Err(false));
+    clock.advance(Duration::from_secs(59));
+    assert_eq!(breaker.state(), BreakerState::Open);
+    assert_eq!(send(req.clone(), &client, &breaker), Err(true));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(breaker.state(), BreakerState::HalfOpen);
+    client.healthy.store(true, Ordering::SeqCst);
+    assert_eq!(send(req, &client, &breaker), Ok(()));
+    assert_eq!(breaker.state(), BreakerState::Closed);
+}
@@ -0,0 +1,148 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::{executor::block_on, pin_mut, prelude::*};
+
+use adventure::cache::{CacheStore, LruCache};
+use adventure::prelude::*;
+use adventure::response::*;
+
+use crate::fixture::ManualClock;
+
+#[derive(Default)]
+struct Client {
+    called: AtomicUsize,
+}
+
+type Resp = FutureResponseObj<'static, String, String>;
+
+#[derive(Clone)]
+struct GetQueueUrl {
+    name: &'static str,
+}
+
+impl BaseRequest for GetQueueUrl {
+    type Ok = String;
+    type Error = String;
+}
+
+impl Request<&Client> for GetQueueUrl {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let i = client.called.fetch_add(1, Ordering::SeqCst);
+        if self.name.is_empty() {
+            Resp::new(future::err(format!("not found ({})", i)))
+        } else {
+            Resp::new(future::ok(format!("{}#{}", self.name, i)))
+        }
+    }
+}
+
+impl RequestKey for GetQueueUrl {
+    type Key = &'static str;
+
+    fn key(&self) -> Self::Key {
+        self.name
+    }
+}
+
+type Store = LruCache<&'static str, Result<String, String>, ManualClock>;
+
+fn send<R>(req: R, client: &Client) -> Result<String, String>
+where
+    R: for<'a> Request<&'a Client, Ok = String, Error = String>,
+{
+    pin_mut!(req);
+    block_on(req.send(client).into_future())
+}
+
+#[test]
+fn cached_ttl() {
+    let client = Client::default();
+    let clock = ManualClock::default();
+    let store = Store::with_clock(10, clock.clone());
+    let req = GetQueueUrl { name: "jobs" }.cached(&store, Duration::from_secs(60));
+    pin_mut!(req);
+
+    assert_eq!(block_on(req.as_mut().send(&client)).unwrap(), "jobs#0");
+    assert_eq!(block_on(req.as_mut().send(&client)).unwrap(), "jobs#0");
+
+    let other = GetQueueUrl { name: "jobs" }.cached(&store, Duration::from_secs(60));
+    assert_eq!(send(other, &client).unwrap(), "jobs#0");
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+
+    clock.advance(Duration::from_secs(59));
+    assert_eq!(block_on(req.as_mut().send(&client)).unwrap(), "jobs#0");
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(block_on(req.send(&client)).unwrap(), "jobs#1");
+}
+
+#[test]
+fn cached_negative() {
+    let client = Client::default();
+    let store = Store::with_clock(10, ManualClock::default());
+    let get = |negative| {
+        let req = GetQueueUrl { name: "" }.cached(&store, Duration::from_secs(60));
+        let req = if negative {
+            req.with_negative_ttl(Duration::from_secs(60))
+        } else {
+            req
+        };
+        send(req, &client).unwrap_err()
+    };
+
+    assert_eq!(get(false), "not found (0)");
+    assert_eq!(get(false), "not found (1)");
+    assert_eq!(get(true), "not found (2)");
+    assert_eq!(get(true), "not found (2)");
+}
+
+#[test]
+fn cached_stale_while_revalidate() {
+    let client = Client::default();
+    let clock = ManualClock::default();
+    let store = Store::with_clock(10, clock.clone());
+    let refreshes = Mutex::new(vec![]);
+    let req = || {
+        GetQueueUrl { name: "jobs" }
+            .cached(&store, Duration::from_secs(60))
+            .with_stale_while_revalidate(Duration::from_secs(60), |refresh| {
+                refreshes.lock().unwrap().push(refresh)
+            })
+    };
+
+    assert_eq!(send(req(), &client).unwrap(), "jobs#0");
+    clock.advance(Duration::from_secs(60));
+
+    // the first request met the stale entry is not kept waiting either
+    assert_eq!(send(req(), &client).unwrap(), "jobs#0");
+    assert_eq!(send(req(), &client).unwrap(), "jobs#0");
+    assert_eq!(client.called.load(Ordering::SeqCst), 2);
+
+    let refresh = refreshes.lock().unwrap().pop().unwrap();
+    block_on(refresh);
+    assert!(refreshes.lock().unwrap().is_empty());
+    assert_eq!(send(req(), &client).unwrap(), "jobs#1");
+    assert_eq!(client.called.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn lru_eviction() {
+    let client = Client::default();
+    let store = Store::with_clock(2, ManualClock::default());
+    let get = |name| {
+        let req = GetQueueUrl { name }.cached(&store, Duration::from_secs(60));
+        send(req, &client).unwrap()
+    };
+
+    assert_eq!(get("a"), "a#0");
+    assert_eq!(get("b"), "b#1");
+    assert_eq!(get("a"), "a#0");
+    assert_eq!(get("c"), "c#2");
+    assert_eq!(store.len(), 2);
+    assert!(store.with_entry(&"b", |_| ()).is_none());
+    assert_eq!(get("a"), "a#0");
+}
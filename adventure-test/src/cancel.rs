@@ -0,0 +1,109 @@
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use futures::prelude::*;
+use tokio::time::delay_for;
+
+use adventure::cancel::CancellationToken;
+use adventure::prelude::*;
+use adventure::request_fn;
+use adventure::retry::ExponentialBackoff;
+
+use crate::fixture::{Client, ListItems};
+
+#[tokio::test]
+async fn cancelled_wakes() {
+    let token = CancellationToken::new();
+    let cancelled = token.cancelled();
+    assert!(!token.is_cancelled());
+
+    let cloned = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        cloned.cancel();
+    });
+    cancelled.await;
+    assert!(token.is_cancelled());
+
+    // resolves immediately once cancelled.
+    token.cancelled().await;
+}
+
+#[tokio::test]
+async fn retry_cancelled() {
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_secs(10);
+    backoff.as_mut().randomization_factor = 0.0;
+
+    let token = CancellationToken::new();
+    let cloned = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        cloned.cancel();
+    });
+
+    let started = Instant::now();
+    let res = ListItems { page: 1 }
+        .retry_with_backoff(backoff)
+        .with_cancellation(token.clone())
+        .send_once(&client)
+        .await;
+    let err = res.unwrap_err();
+    assert!(err.is_cancelled());
+    assert!(!err.is_timeout());
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+
+    // already cancelled, so it fails at the first wait.
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let res = ListItems { page: 1 }
+        .retry()
+        .with_cancellation(token)
+        .send_once(&client)
+        .await;
+    assert!(res.unwrap_err().is_cancelled());
+}
+
+#[tokio::test]
+async fn retry_cancelled_in_attempt() {
+    let token = CancellationToken::new();
+    let cloned = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        cloned.cancel();
+    });
+
+    let res = request_fn(|_: ()| async {
+        delay_for(Duration::from_millis(50)).await;
+        Ok::<_, String>(1)
+    })
+    .with_should_retry(|_: &String, _| true)
+    .retry()
+    .with_attempt_timeout(Duration::from_secs(10))
+    .with_deadline(Instant::now() + Duration::from_secs(10))
+    .with_cancellation(token)
+    .send_once(())
+    .await;
+    assert_eq!(res.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn paginate_cancelled() {
+    let client = Client::default();
+    let token = CancellationToken::new();
+    let mut pages = ListItems { page: 0 }
+        .paginate(&client)
+        .with_cancellation(token.clone());
+
+    assert_eq!(pages.next().await, Some(Ok(0)));
+    token.cancel();
+    assert_eq!(pages.next().await, None);
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+}
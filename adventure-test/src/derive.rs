@@ -0,0 +1,120 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use futures::prelude::*;
+
+use adventure::prelude::*;
+use adventure::response::*;
+
+#[derive(Default)]
+struct Client {
+    called: AtomicUsize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ListUsersResponse {
+    users: Vec<String>,
+    next_token: Option<usize>,
+}
+
+#[derive(Clone, BaseRequest, Request, PagedRequest)]
+#[adventure(ok = ListUsersResponse, error = String)]
+#[adventure(
+    client = &'a Client,
+    response = LocalFutureResponseObj<'a, ListUsersResponse, String>,
+    send = list_users,
+    cursor = next_token,
+)]
+struct ListUsers {
+    prefix: &'static str,
+    next_token: Option<usize>,
+}
+
+fn list_users<'a>(
+    req: &ListUsers,
+    client: &'a Client,
+) -> LocalFutureResponseObj<'a, ListUsersResponse, String> {
+    let token = req.next_token.unwrap_or(0);
+    let prefix = req.prefix;
+    LocalFutureResponseObj::new(async move {
+        client.called.fetch_add(1, Ordering::SeqCst);
+        if prefix.is_empty() {
+            return Err("empty prefix".to_owned());
+        }
+        Ok(ListUsersResponse {
+            users: vec![format!("{}{}", prefix, token)],
+            next_token: if token < 2 { Some(token + 1) } else { None },
+        })
+    })
+}
+
+#[derive(BaseRequest, Request)]
+#[adventure(ok = T, error = String)]
+#[adventure(
+    client = &'a Client,
+    response = FutureResponseObj<'a, T, String>,
+    send = Echo::send_to,
+)]
+struct Echo<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    value: T,
+}
+
+impl<T> Echo<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    fn send_to<'a>(&self, client: &'a Client) -> FutureResponseObj<'a, T, String> {
+        client.called.fetch_add(1, Ordering::SeqCst);
+        FutureResponseObj::new(future::ok(self.value.clone()))
+    }
+}
+
+#[tokio::test]
+async fn derive_send() {
+    let client = Client::default();
+    let res = ListUsers {
+        prefix: "user",
+        next_token: None,
+    }
+    .send_once(&client)
+    .await;
+    assert_eq!(res.unwrap().users, vec!["user0"]);
+
+    let res = ListUsers {
+        prefix: "",
+        next_token: None,
+    }
+    .send_once(&client)
+    .await;
+    assert_eq!(res.unwrap_err(), "empty prefix");
+    assert_eq!(client.called.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn derive_paginate() {
+    let client = Client::default();
+    let users: Vec<_> = ListUsers {
+        prefix: "user",
+        next_token: None,
+    }
+    .paginate(&client)
+    .map_ok(|page| page.users)
+    .try_concat()
+    .await
+    .unwrap();
+
+    assert_eq!(users, vec!["user0", "user1", "user2"]);
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn derive_generic() {
+    let client = Client::default();
+    let res = Echo { value: 42 }.send_once(&client).await;
+    assert_eq!(res.unwrap(), 42);
+
+    let res = Echo { value: "hello" }.send_once(&client).await;
+    assert_eq!(res.unwrap(), "hello");
+}
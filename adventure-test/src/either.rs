@@ -0,0 +1,116 @@
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use futures::prelude::*;
+
+use adventure::either::Either;
+use adventure::prelude::*;
+use adventure::retry::ExponentialBackoff;
+use adventure::{race, zip};
+
+use crate::fixture::{Client, ListItems, Resp};
+
+#[derive(Clone)]
+struct Delayed {
+    value: Result<usize, String>,
+    delay: Duration,
+}
+
+impl BaseRequest for Delayed {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<&Client> for Delayed {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, _client: &Client) -> Self::Response {
+        let value = self.value.clone();
+        let delay = tokio::time::delay_for(self.delay);
+        Resp::new(delay.then(move |_| future::ready(value)))
+    }
+}
+
+impl RetriableRequest for Delayed {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        false
+    }
+}
+
+impl PagedRequest for Delayed {
+    fn advance(&mut self, _response: &Self::Ok) -> bool {
+        false
+    }
+}
+
+fn delayed(value: Result<usize, &str>, millis: u64) -> Delayed {
+    Delayed {
+        value: value.map_err(str::to_owned),
+        delay: Duration::from_millis(millis),
+    }
+}
+
+#[tokio::test]
+async fn either_paginate_retry() {
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_millis(10);
+
+    let req: Either<ListItems, Delayed> = Either::Left(ListItems { page: 0 });
+    let pages: Vec<_> = req
+        .retry_with_backoff(backoff)
+        .paginate(&client)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![0, 1, 2]);
+    assert_eq!(client.called.load(Ordering::SeqCst), 4);
+
+    let req: Either<ListItems, Delayed> = Either::Right(delayed(Ok(7), 1));
+    let res = req.oneshot().send_once(&client).await;
+    assert_eq!(res.unwrap(), 7);
+}
+
+#[tokio::test]
+async fn zip_send() {
+    let client = Client::default();
+    let res = zip(ListItems { page: 1 }, delayed(Ok(2), 10))
+        .oneshot()
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), (1, 2));
+
+    let res = delayed(Ok(1), 100)
+        .zip(delayed(Err("failed"), 10))
+        .oneshot()
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap_err(), "failed");
+}
+
+#[tokio::test]
+async fn race_send() {
+    let client = Client::default();
+    let res = race(delayed(Ok(1), 100), delayed(Ok(2), 10))
+        .oneshot()
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 2);
+
+    let res = delayed(Err("first"), 10)
+        .race(delayed(Ok(2), 50))
+        .oneshot()
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 2);
+
+    let res = race(delayed(Err("first"), 10), delayed(Err("second"), 50))
+        .oneshot()
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap_err(), "second");
+}
@@ -0,0 +1,145 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::prelude::*;
+
+use adventure::executor::{Executor, Progress};
+use adventure::prelude::*;
+use adventure::rate_limit::TokenBucket;
+use adventure::response::*;
+use adventure::retry::{ExponentialBackoff, TokioTimer};
+
+use crate::fixture::backoff;
+
+#[derive(Default)]
+struct Client {
+    in_flight: AtomicUsize,
+    max_in_flight: Mutex<usize>,
+}
+
+type Resp = FutureResponseObj<'static, usize, String>;
+
+#[derive(Clone)]
+struct Tag {
+    id: usize,
+    attempts: Arc<AtomicUsize>,
+    fail_until: usize,
+}
+
+fn tag(id: usize, fail_until: usize) -> Tag {
+    Tag {
+        id,
+        attempts: Default::default(),
+        fail_until,
+    }
+}
+
+impl BaseRequest for Tag {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl OneshotRequest<Arc<Client>> for Tag {
+    type Response = Resp;
+
+    fn send_once(self, client: Arc<Client>) -> Self::Response {
+        let i = self.attempts.fetch_add(1, Ordering::SeqCst);
+        let n = client.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        {
+            let mut max = client.max_in_flight.lock().unwrap();
+            *max = (*max).max(n);
+        }
+
+        let result = if i < self.fail_until {
+            Err(format!("{} failed", self.id))
+        } else {
+            Ok(self.id)
+        };
+        Resp::new(async move {
+            tokio::time::delay_for(Duration::from_millis(10)).await;
+            client.in_flight.fetch_sub(1, Ordering::SeqCst);
+            result
+        })
+    }
+}
+
+impl RetriableRequest for Tag {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        true
+    }
+}
+
+fn limited_backoff() -> ExponentialBackoff {
+    let mut backoff = backoff();
+    backoff.as_mut().max_elapsed_time = Some(Duration::from_millis(100));
+    backoff
+}
+
+#[tokio::test]
+async fn execute_report() {
+    let client = Arc::new(Client::default());
+    let progress = Mutex::new(Vec::new());
+    let requests = stream::iter(vec![tag(0, 0), tag(1, 2), tag(2, 0), tag(3, 100)]);
+
+    let report = Executor::new(client, TokioTimer)
+        .with_backoff(limited_backoff())
+        .with_progress(|p: &Progress| progress.lock().unwrap().push(*p))
+        .execute(requests)
+        .await;
+
+    let mut successes: Vec<_> = report.successes().iter().map(|&(i, _)| i).collect();
+    successes.sort();
+    assert_eq!(successes, vec![0, 1, 2]);
+    assert_eq!(report.failures().len(), 1);
+    assert_eq!(report.failures()[0].0, 3);
+    assert!(report.failures()[0].1.is_timeout());
+    assert!(report.retries() >= 3);
+
+    let progress = progress.into_inner().unwrap();
+    assert_eq!(progress.len(), 4);
+    let last = progress.last().unwrap();
+    assert_eq!(last.succeeded(), 3);
+    assert_eq!(last.failed(), 1);
+    assert_eq!(last.in_flight(), 0);
+    assert_eq!(last.retries(), report.retries());
+}
+
+#[tokio::test]
+async fn execute_concurrency() {
+    let client = Arc::new(Client::default());
+    let requests = stream::iter((0..10).map(|id| tag(id, 0)));
+
+    let report = Executor::new(client.clone(), TokioTimer)
+        .with_concurrency(3)
+        .execute(requests)
+        .await;
+    assert_eq!(report.successes().len(), 10);
+    assert_eq!(*client.max_in_flight.lock().unwrap(), 3);
+
+    let requests = stream::iter((0..4).map(|id| tag(id, 1)));
+    let report = Executor::new(client, TokioTimer)
+        .with_backoff(limited_backoff())
+        .with_predicate(|_: &Tag, e: &String, _| e != "0 failed")
+        .execute(requests)
+        .await;
+    assert_eq!(report.successes().len(), 3);
+    assert_eq!(report.retries(), 3);
+    let (_, failures) = report.into_parts();
+    assert_eq!(failures[0].0, 0);
+    assert_eq!(failures[0].1.as_inner().unwrap(), "0 failed");
+}
+
+#[tokio::test]
+async fn execute_rate_limit() {
+    let client = Arc::new(Client::default());
+    let requests = stream::iter((0..4).map(|id| tag(id, 0)));
+
+    let start = Instant::now();
+    let report = Executor::new(client, TokioTimer)
+        .with_rate_limit(TokenBucket::new(1, Duration::from_millis(50)))
+        .execute(requests)
+        .await;
+    assert_eq!(report.successes().len(), 4);
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
@@ -0,0 +1,129 @@
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::prelude::*;
+
+use adventure::boxed::BoxRequest;
+use adventure::extensions::Extensions;
+use adventure::layer::Middleware;
+use adventure::prelude::*;
+
+use crate::fixture::{Client, Resp};
+
+#[derive(Clone)]
+struct GetItem {
+    id: usize,
+}
+
+impl BaseRequest for GetItem {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<&Client> for GetItem {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let id = self.id;
+        Resp::new(future::ready(client.call().map(|()| id)))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct TraceId(u64);
+
+#[derive(Debug, PartialEq)]
+struct Tenant(&'static str);
+
+struct Idempotent;
+
+fn trace_id<R: BaseRequest>(req: &R) -> Option<u64> {
+    req.extensions()
+        .and_then(Extensions::get::<TraceId>)
+        .map(|t| t.0)
+}
+
+#[tokio::test]
+async fn extensions_through_adaptors() {
+    let req = GetItem { id: 1 };
+    assert!(req.extensions().is_none());
+
+    let req = req
+        .with_extension(TraceId(42))
+        .map_ok(|id| id * 2)
+        .retry_if(|_: &_, _: &_, _| true)
+        .with_extension(Tenant("spoqa"))
+        .oneshot()
+        .repeat();
+    assert_eq!(trace_id(&req), Some(42));
+    let extensions = req.extensions().unwrap();
+    assert_eq!(extensions.get::<Tenant>(), Some(&Tenant("spoqa")));
+    assert_eq!(extensions.len(), 2);
+
+    let boxed: BoxRequest<'_, &Client, usize, String> =
+        BoxRequest::new(GetItem { id: 1 }.with_extension(TraceId(7)));
+    assert_eq!(trace_id(&boxed), Some(7));
+}
+
+#[tokio::test]
+async fn extensions_in_retrial_predicate() {
+    let pred = |req: &_, _: &String, _: Duration| {
+        let req: &adventure::extensions::WithExtensions<GetItem> = req;
+        req.extensions().unwrap().get::<Idempotent>().is_some()
+    };
+
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let res = GetItem { id: 3 }
+        .with_extension(Idempotent)
+        .retry_if(pred)
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 3);
+
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+    let res = GetItem { id: 3 }
+        .with_extension(TraceId(1))
+        .retry_if(pred)
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap_err().into_inner().unwrap(), "0 failed");
+}
+
+#[derive(Clone)]
+struct Logging<'a> {
+    logs: &'a Mutex<Vec<u64>>,
+}
+
+impl<'a, R, C> Middleware<R, C> for Logging<'a>
+where
+    R: Request<C>,
+{
+    type Response = R::Response;
+
+    fn send(&self, req: Pin<&mut R>, client: C) -> Self::Response {
+        if let Some(id) = trace_id(&*req) {
+            self.logs.lock().unwrap().push(id);
+        }
+        req.send(client)
+    }
+}
+
+#[tokio::test]
+async fn extensions_in_middleware() {
+    let logs = Mutex::new(vec![]);
+    let client = Client::default();
+    let res = GetItem { id: 5 }
+        .with_extension(TraceId(9))
+        .with_middleware(Logging { logs: &logs })
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(*logs.lock().unwrap(), vec![9]);
+}
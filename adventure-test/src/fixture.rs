@@ -0,0 +1,91 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::prelude::*;
+
+use adventure::breaker::Clock;
+use adventure::prelude::*;
+use adventure::response::*;
+use adventure::retry::ExponentialBackoff;
+
+#[derive(Default)]
+pub(crate) struct Client {
+    pub(crate) called: AtomicUsize,
+    pub(crate) fail_until: usize,
+}
+
+impl Client {
+    /// Counts a call, which fails until `fail_until` calls have been made.
+    pub(crate) fn call(&self) -> Result<(), String> {
+        let i = self.called.fetch_add(1, Ordering::SeqCst);
+        if i < self.fail_until {
+            Err(format!("{} failed", i))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub(crate) type Resp = FutureResponseObj<'static, usize, String>;
+
+#[derive(Clone)]
+pub(crate) struct ListItems {
+    pub(crate) page: usize,
+}
+
+impl BaseRequest for ListItems {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<&Client> for ListItems {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let page = self.page;
+        Resp::new(future::ready(client.call().map(|()| page)))
+    }
+}
+
+impl RetriableRequest for ListItems {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        true
+    }
+}
+
+impl PagedRequest for ListItems {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.page = response + 1;
+        self.page < 3
+    }
+}
+
+pub(crate) fn backoff() -> ExponentialBackoff {
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_millis(10);
+    backoff
+}
+
+/// A clock which advances only when told to.
+#[derive(Clone)]
+pub(crate) struct ManualClock(Arc<Mutex<Instant>>);
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock(Arc::new(Mutex::new(Instant::now())))
+    }
+}
+
+impl ManualClock {
+    pub(crate) fn advance(&self, duration: Duration) {
+        *self.0.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.0.lock().unwrap()
+    }
+}
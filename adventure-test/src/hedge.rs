@@ -0,0 +1,78 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::{pin_mut, prelude::*};
+use tokio::time::timeout;
+
+use adventure::prelude::*;
+use adventure::response::*;
+
+#[derive(Default)]
+struct Client {
+    called: AtomicUsize,
+    slow_until: usize,
+}
+
+type Resp = FutureResponseObj<'static, usize, String>;
+
+#[derive(Clone)]
+struct DescribeService;
+
+impl BaseRequest for DescribeService {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<&Client> for DescribeService {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let i = client.called.fetch_add(1, Ordering::SeqCst);
+        if i < client.slow_until {
+            Resp::new(future::pending())
+        } else {
+            Resp::new(future::ok(i))
+        }
+    }
+}
+
+impl IdempotentRequest for DescribeService {}
+
+#[tokio::test]
+async fn hedge_fast() {
+    let client = Client::default();
+    let req = DescribeService.hedge(Duration::from_millis(10));
+
+    assert_eq!(req.send_once(&client).await.unwrap(), 0);
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn hedge_slow() {
+    let client = Client {
+        slow_until: 1,
+        ..Default::default()
+    };
+    let req = DescribeService.hedge(Duration::from_millis(10));
+    pin_mut!(req);
+
+    assert_eq!(req.as_mut().send(&client).await.unwrap(), 1);
+    assert_eq!(req.send(&client).await.unwrap(), 2);
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn hedge_max_hedges() {
+    let client = Client {
+        slow_until: 10,
+        ..Default::default()
+    };
+    let req = DescribeService
+        .hedge(Duration::from_millis(10))
+        .with_max_hedges(3);
+
+    let res = timeout(Duration::from_millis(200), req.send_once(&client)).await;
+    assert!(res.is_err());
+    assert_eq!(client.called.load(Ordering::SeqCst), 4);
+}
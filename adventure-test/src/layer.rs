@@ -0,0 +1,77 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::prelude::*;
+
+use adventure::layer::{layer_fn, Middleware, RequestBuilder, WithMiddleware};
+use adventure::prelude::*;
+use adventure::response::*;
+use adventure::retry::ExponentialBackoff;
+
+use crate::fixture::{Client, ListItems};
+
+#[derive(Clone, Default)]
+struct Counting {
+    sent: Arc<AtomicUsize>,
+    failed: Arc<AtomicUsize>,
+}
+
+impl<R, C> Middleware<R, C> for Counting
+where
+    R: Request<C>,
+    R::Response: Send + 'static,
+    R::Ok: Send + 'static,
+    R::Error: Send + 'static,
+{
+    type Response = FutureResponseObj<'static, R::Ok, R::Error>;
+
+    fn send(&self, req: Pin<&mut R>, client: C) -> Self::Response {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        let failed = self.failed.clone();
+        FutureResponseObj::new(req.send(client).into_future().map_err(move |e| {
+            failed.fetch_add(1, Ordering::SeqCst);
+            e
+        }))
+    }
+}
+
+#[tokio::test]
+async fn middleware_paginate() {
+    let client = Client::default();
+    let counting = Counting::default();
+    let pages: Vec<_> = ListItems { page: 0 }
+        .with_middleware(counting.clone())
+        .paginate(&client)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(pages, vec![0, 1, 2]);
+    assert_eq!(counting.sent.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn layer_stack() {
+    let client = Client {
+        fail_until: 2,
+        ..Default::default()
+    };
+    let counting = Counting::default();
+    let stack = RequestBuilder::new()
+        .layer(layer_fn(|req: WithMiddleware<ListItems, Counting>| {
+            let mut backoff = ExponentialBackoff::default();
+            backoff.as_mut().initial_interval = Duration::from_millis(10);
+            req.retry_with_backoff(backoff)
+        }))
+        .middleware(counting.clone());
+
+    let res = stack
+        .request(ListItems { page: 7 })
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 7);
+    assert_eq!(counting.sent.load(Ordering::SeqCst), 3);
+    assert_eq!(counting.failed.load(Ordering::SeqCst), 2);
+}
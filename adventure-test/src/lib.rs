@@ -1,6 +1,56 @@
 #![deny(rust_2018_idioms)]
 
+#[cfg(test)]
+mod and_then;
+#[cfg(test)]
+mod batch;
+#[cfg(test)]
+mod batcher;
+#[cfg(test)]
+mod blocking;
+#[cfg(test)]
+mod boxed;
+#[cfg(test)]
+mod breaker;
+#[cfg(test)]
+mod cache;
+#[cfg(test)]
+mod cancel;
+#[cfg(test)]
+mod derive;
+#[cfg(test)]
+mod either;
+#[cfg(test)]
+mod executor;
+#[cfg(test)]
+mod extensions;
+#[cfg(test)]
+mod fixture;
+#[cfg(test)]
+mod hedge;
+#[cfg(test)]
+mod layer;
+#[cfg(test)]
+mod map;
+#[cfg(test)]
+mod or_else;
 #[cfg(test)]
 mod paginator;
 #[cfg(test)]
+mod pool;
+#[cfg(test)]
+mod rate_limit;
+#[cfg(test)]
+mod request_fn;
+#[cfg(test)]
 mod retry;
+#[cfg(test)]
+mod shared;
+#[cfg(test)]
+mod single_flight;
+#[cfg(test)]
+mod timeout;
+#[cfg(test)]
+mod tower;
+#[cfg(test)]
+mod util;
@@ -0,0 +1,134 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{executor::block_on_stream, pin_mut, prelude::*};
+
+use adventure::prelude::*;
+use adventure::response::*;
+
+#[derive(Clone, Debug, Default)]
+struct Numbers {
+    current: Arc<AtomicUsize>,
+    fail_until: usize,
+    end: usize,
+}
+
+impl Numbers {
+    fn new(start: usize, fail_until: usize, end: usize) -> Self {
+        Numbers {
+            current: Arc::new(AtomicUsize::new(start)),
+            fail_until,
+            end,
+        }
+    }
+}
+
+type Resp = FutureResponseObj<'static, usize, String>;
+
+impl BaseRequest for Numbers {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl<C> OneshotRequest<C> for Numbers {
+    type Response = Resp;
+
+    fn send_once(mut self, client: C) -> Self::Response {
+        Pin::new(&mut self).send(client)
+    }
+}
+
+impl<C> Request<C> for Numbers {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, _client: C) -> Self::Response {
+        let i = self.current.load(Ordering::SeqCst);
+        if i < self.fail_until {
+            self.current.fetch_add(1, Ordering::SeqCst);
+            Resp::new(future::err(format!("{} tried", i)))
+        } else {
+            Resp::new(future::ok(i))
+        }
+    }
+}
+
+impl RetriableRequest for Numbers {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        true
+    }
+}
+
+impl PagedRequest for Numbers {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        if *response < self.end {
+            self.current.fetch_add(1, Ordering::SeqCst);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[tokio::test]
+async fn map_ok_send() {
+    let req = Numbers::new(3, 0, 3).map_ok(|i| i * 2);
+    pin_mut!(req);
+
+    assert_eq!(req.as_mut().send(()).await, Ok(6));
+    assert_eq!(req.send(()).await, Ok(6));
+}
+
+#[tokio::test]
+async fn map_err_send_once() {
+    let req = Numbers::new(1, 2, 2).map_err(|e| e.len());
+
+    assert_eq!(req.send_once(()).await, Err(7));
+}
+
+#[tokio::test]
+async fn map_ok_retry() {
+    let req = Numbers::new(1, 5, 5).map_ok(|i| i.to_string()).retry();
+
+    assert_eq!(req.send_once(()).await.unwrap(), "5");
+}
+
+#[test]
+fn map_err_paginate() {
+    let req = Numbers::new(1, 0, 3).map_err(|_| ());
+    let pages: Result<Vec<_>, _> = block_on_stream(req.paginate(())).collect();
+
+    assert_eq!(pages, Ok(vec![1, 2, 3]));
+}
+
+#[derive(Debug, PartialEq)]
+struct Page(usize);
+
+#[tokio::test]
+async fn map_ok_paginate() {
+    let req = Numbers::new(1, 2, 3)
+        .map_ok(Page)
+        .with_reverse(|page| Some(&page.0))
+        .retry();
+    let pages: Result<Vec<_>, _> = req.paginate(()).try_collect().await;
+
+    assert_eq!(pages.unwrap(), vec![Page(2), Page(3)]);
+}
+
+#[derive(Debug, PartialEq)]
+enum AppError {
+    Inner(String),
+}
+
+#[tokio::test]
+async fn map_err_retry() {
+    let req = Numbers::new(1, 3, 3)
+        .map_err(AppError::Inner)
+        .with_reverse(|e| match e {
+            AppError::Inner(e) => Some(e),
+        })
+        .retry();
+
+    assert_eq!(req.send_once(()).await.unwrap(), 3);
+}
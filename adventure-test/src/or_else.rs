@@ -0,0 +1,103 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::{pin_mut, prelude::*};
+
+use adventure::prelude::*;
+use adventure::response::*;
+use adventure::retry::ExponentialBackoff;
+
+#[derive(Default)]
+struct Client {
+    primary: AtomicUsize,
+    replica: AtomicUsize,
+    healthy: bool,
+}
+
+type Resp = FutureResponseObj<'static, &'static str, String>;
+
+#[derive(Clone)]
+struct Primary;
+
+impl BaseRequest for Primary {
+    type Ok = &'static str;
+    type Error = String;
+}
+
+impl Request<&Client> for Primary {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let i = client.primary.fetch_add(1, Ordering::SeqCst);
+        if client.healthy {
+            Resp::new(future::ok("primary"))
+        } else {
+            Resp::new(future::err(format!("{} tried", i)))
+        }
+    }
+}
+
+impl RetriableRequest for Primary {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        true
+    }
+}
+
+struct Replica;
+
+impl BaseRequest for Replica {
+    type Ok = &'static str;
+    type Error = String;
+}
+
+impl OneshotRequest<&Client> for Replica {
+    type Response = Resp;
+
+    fn send_once(self, client: &Client) -> Self::Response {
+        client.replica.fetch_add(1, Ordering::SeqCst);
+        Resp::new(future::ok("replica"))
+    }
+}
+
+#[tokio::test]
+async fn or_else_primary() {
+    let client = Client {
+        healthy: true,
+        ..Default::default()
+    };
+    let req = Primary.oneshot().or_else(|_| Ok(Replica));
+
+    assert_eq!(req.send_once(&client).await.unwrap(), "primary");
+    assert_eq!(client.replica.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn or_else_fallback() {
+    let client = Client::default();
+    let req = Primary.or_else(|e| if e == "0 tried" { Ok(Replica) } else { Err(e) });
+    pin_mut!(req);
+
+    assert_eq!(req.as_mut().send(&client).await.unwrap(), "replica");
+    assert_eq!(req.send(&client).await.unwrap_err(), "1 tried");
+    assert_eq!(client.primary.load(Ordering::SeqCst), 2);
+    assert_eq!(client.replica.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn or_else_retry_exhausted() {
+    let client = Client::default();
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_millis(10);
+    backoff.as_mut().max_elapsed_time = Some(Duration::from_millis(100));
+    let req = Primary.retry_with_backoff(backoff).or_else(|e| {
+        if e.is_timeout() {
+            Ok(Replica)
+        } else {
+            Err(e.to_string())
+        }
+    });
+
+    assert_eq!(req.send_once(&client).await.unwrap(), "replica");
+    assert!(client.primary.load(Ordering::SeqCst) > 1);
+}
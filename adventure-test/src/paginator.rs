@@ -8,6 +8,7 @@ use adventure::{
     paginator::PagedRequest,
     request::{BaseRequest, Request},
     response::LocalFutureResponseObj,
+    util::RequestExt,
 };
 use futures::{
     executor::{block_on, block_on_stream},
@@ -0,0 +1,125 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::prelude::*;
+
+use adventure::pool::{Balance, ClientPool, PooledResponse};
+use adventure::prelude::*;
+use adventure::response::*;
+
+#[derive(Default)]
+struct Endpoint {
+    id: usize,
+    healthy: bool,
+    called: AtomicUsize,
+}
+
+impl Endpoint {
+    fn new(id: usize, healthy: bool) -> Self {
+        Endpoint {
+            id,
+            healthy,
+            ..Default::default()
+        }
+    }
+}
+
+type Resp = FutureResponseObj<'static, usize, String>;
+
+#[derive(Clone, Default)]
+struct ListItems {
+    page: usize,
+}
+
+impl BaseRequest for ListItems {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<&Endpoint> for ListItems {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Endpoint) -> Self::Response {
+        client.called.fetch_add(1, Ordering::SeqCst);
+        if client.healthy {
+            Resp::new(future::ok(client.id * 10 + self.page))
+        } else {
+            Resp::new(future::err(format!("{} failed", client.id)))
+        }
+    }
+}
+
+impl PagedRequest for ListItems {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.page = response % 10 + 1;
+        self.page < 4
+    }
+}
+
+type Pool<'a> = ClientPool<&'a Endpoint>;
+
+fn send<'a>(pool: &'a Pool<'a>) -> PooledResponse<Resp, &'a Pool<'a>, ()> {
+    ListItems::default().oneshot().pooled().send_once(pool)
+}
+
+#[tokio::test]
+async fn pool_round_robin_paginate() {
+    let endpoints = [Endpoint::new(1, true), Endpoint::new(2, true)];
+    let pool = ClientPool::new(endpoints.iter());
+
+    let pages: Vec<_> = ListItems::default()
+        .pooled()
+        .paginate(&pool)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![10, 21, 12, 23]);
+    assert_eq!(pool.in_flight(0), Some(0));
+    assert_eq!(pool.in_flight(1), Some(0));
+    assert_eq!(pool.in_flight(2), None);
+}
+
+#[tokio::test]
+async fn pool_ejects_failing_clients() {
+    let endpoints = [Endpoint::new(1, false), Endpoint::new(2, true)];
+    let pool = ClientPool::new(endpoints.iter()).with_ejection(0.5, 2, Duration::from_millis(50));
+
+    for _ in 0..4 {
+        let _ = send(&pool).await;
+    }
+    assert_eq!(pool.is_ejected(0), Some(true));
+    assert_eq!(pool.is_ejected(1), Some(false));
+    assert_eq!(endpoints[0].called.load(Ordering::SeqCst), 2);
+
+    for _ in 0..4 {
+        let res = send(&pool).await;
+        assert_eq!(res.unwrap(), 20);
+    }
+    assert_eq!(endpoints[0].called.load(Ordering::SeqCst), 2);
+
+    tokio::time::delay_for(Duration::from_millis(60)).await;
+    assert_eq!(pool.is_ejected(0), Some(false));
+}
+
+#[tokio::test]
+async fn pool_least_in_flight() {
+    let endpoints = [Endpoint::new(1, true), Endpoint::new(2, true)];
+    let pool = ClientPool::new(endpoints.iter()).with_balance(Balance::LeastInFlight);
+
+    let a = send(&pool);
+    let b = send(&pool);
+    assert_ne!(a.index(), b.index());
+    assert_eq!(pool.in_flight(a.index()), Some(1));
+
+    let index = a.index();
+    drop(a);
+    assert_eq!(pool.in_flight(index), Some(0));
+    let c = send(&pool);
+    assert_eq!(c.index(), index);
+
+    assert_eq!(b.await.unwrap() % 10, 0);
+    assert_eq!(c.await.unwrap() % 10, 0);
+    assert_eq!(pool.in_flight(0), Some(0));
+    assert_eq!(pool.in_flight(1), Some(0));
+}
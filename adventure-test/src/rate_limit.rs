@@ -0,0 +1,99 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::prelude::*;
+
+use adventure::prelude::*;
+use adventure::rate_limit::{RateLimiter, TokenBucket};
+use adventure::response::*;
+
+use crate::fixture::ManualClock;
+
+type Resp = FutureResponseObj<'static, usize, String>;
+
+#[derive(Clone)]
+struct ListPage {
+    page: usize,
+}
+
+impl BaseRequest for ListPage {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<()> for ListPage {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, _client: ()) -> Self::Response {
+        Resp::new(future::ok(self.page))
+    }
+}
+
+impl PagedRequest for ListPage {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.page = response + 1;
+        self.page < 5
+    }
+}
+
+struct Describe;
+
+impl BaseRequest for Describe {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl OneshotRequest<()> for Describe {
+    type Response = Resp;
+
+    fn send_once(self, _client: ()) -> Self::Response {
+        Resp::new(future::ok(42))
+    }
+}
+
+#[test]
+fn token_bucket_burst() {
+    let clock = ManualClock::default();
+    let bucket = TokenBucket::with_clock(3, Duration::from_secs(1), clock.clone());
+
+    for _ in 0..3 {
+        assert_eq!(bucket.acquire(), Duration::from_secs(0));
+    }
+    assert_eq!(bucket.acquire(), Duration::from_secs(1));
+    assert_eq!(bucket.acquire(), Duration::from_secs(2));
+
+    // three permits are refilled, and two of them have been taken already
+    clock.advance(Duration::from_secs(3));
+    assert_eq!(bucket.acquire(), Duration::from_secs(0));
+    assert_eq!(bucket.acquire(), Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn rate_limit_paginate() {
+    let bucket = Arc::new(TokenBucket::new(2, Duration::from_millis(50)));
+    let started = Instant::now();
+    let pages: Vec<_> = ListPage { page: 0 }
+        .rate_limit(bucket.clone())
+        .paginate(())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(pages, vec![0, 1, 2, 3, 4]);
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn rate_limit_shared() {
+    let bucket = Arc::new(TokenBucket::new(1, Duration::from_millis(100)));
+    let started = Instant::now();
+
+    let page = ListPage { page: 7 }.rate_limit(bucket.clone()).oneshot();
+    assert_eq!(page.send_once(()).await.unwrap(), 7);
+    assert!(started.elapsed() < Duration::from_millis(100));
+
+    let describe = Describe.rate_limit(bucket);
+    assert_eq!(describe.send_once(()).await.unwrap(), 42);
+    assert!(started.elapsed() >= Duration::from_millis(100));
+}
@@ -0,0 +1,67 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use futures::prelude::*;
+
+use adventure::prelude::*;
+use adventure::{oneshot_fn, paged_fn, request_fn};
+
+use crate::fixture::{backoff, Client};
+
+#[tokio::test]
+async fn request_fn_retry() {
+    let client = Arc::new(Client {
+        fail_until: 2,
+        ..Default::default()
+    });
+
+    let res = request_fn(|client: Arc<Client>| async move { client.call().map(|()| 40) })
+        .retry_if(|_: &_, e: &String, _| e.starts_with('0'))
+        .send_once(client.clone())
+        .await;
+    assert_eq!(res.unwrap_err().into_inner().unwrap(), "1 failed");
+
+    let res = request_fn(|client: Arc<Client>| async move { client.call().map(|()| 40) })
+        .with_should_retry(|_: &String, _| true)
+        .retry_with_backoff(backoff())
+        .send_once(client.clone())
+        .await;
+    assert_eq!(res.unwrap(), 40);
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn oneshot_fn_send() {
+    let client = Arc::new(Client::default());
+    let page = 3;
+    let res =
+        oneshot_fn(move |client: Arc<Client>| async move { client.call().map(|()| page * 10) })
+            .send_once(client)
+            .await;
+    assert_eq!(res.unwrap(), 30);
+}
+
+#[tokio::test]
+async fn paged_fn_paginate() {
+    let client = Arc::new(Client {
+        fail_until: 1,
+        ..Default::default()
+    });
+    let pages: Vec<_> = paged_fn(
+        0,
+        |&page: &usize, client: Arc<Client>| async move { client.call().map(|()| page * 10) },
+        |page: &mut usize, _: &usize| {
+            *page += 1;
+            *page < 3
+        },
+    )
+    .with_should_retry(|_: &String, _| true)
+    .retry_with_backoff(backoff())
+    .paginate(client.clone())
+    .try_collect()
+    .await
+    .unwrap();
+
+    assert_eq!(pages, vec![0, 10, 20]);
+    assert_eq!(client.called.load(Ordering::SeqCst), 4);
+}
@@ -1,11 +1,14 @@
 use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use futures::{pin_mut, prelude::*};
 
 use adventure::prelude::*;
+use adventure::request_fn;
 use adventure::response::*;
+use adventure::retry::ExponentialBackoff;
 
 #[derive(Debug, Default)]
 pub(crate) struct Numbers {
@@ -91,3 +94,52 @@ async fn retry_send() {
 
     assert_eq!(res.await.unwrap(), 5);
 }
+
+#[tokio::test]
+async fn retry_attempt_timeout() {
+    let called = Arc::new(AtomicUsize::new(0));
+    let counter = called.clone();
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_millis(10);
+
+    let res = request_fn(move |_: ()| {
+        let i = counter.fetch_add(1, Ordering::SeqCst);
+        async move {
+            if i < 2 {
+                future::pending::<()>().await;
+            }
+            Ok::<_, String>(i)
+        }
+    })
+    .with_should_retry(|_: &String, _| false)
+    .retry_with_backoff(backoff)
+    .with_attempt_timeout(Duration::from_millis(20))
+    .send_once(())
+    .await;
+
+    assert_eq!(res.unwrap(), 2);
+    assert_eq!(called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn retry_deadline() {
+    let numbers = Numbers {
+        current: AtomicUsize::new(0),
+        end: 100,
+    };
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_secs(10);
+    backoff.as_mut().randomization_factor = 0.0;
+
+    let started = Instant::now();
+    let res = numbers
+        .retry_with_backoff(backoff)
+        .with_deadline(Instant::now() + Duration::from_millis(50))
+        .send_once(())
+        .await;
+
+    let err = res.unwrap_err();
+    assert!(err.is_timeout());
+    assert_eq!(err.attempts(), Some(1));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
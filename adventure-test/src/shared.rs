@@ -0,0 +1,109 @@
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::prelude::*;
+
+use adventure::prelude::*;
+use adventure::retry::ExponentialBackoff;
+
+use crate::fixture::{Client, Resp};
+
+#[derive(Default)]
+struct ListItems {
+    page: usize,
+    cloned: Arc<AtomicUsize>,
+}
+
+impl Clone for ListItems {
+    fn clone(&self) -> Self {
+        self.cloned.fetch_add(1, Ordering::SeqCst);
+        ListItems {
+            page: self.page,
+            cloned: self.cloned.clone(),
+        }
+    }
+}
+
+impl BaseRequest for ListItems {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl Request<&Client> for ListItems {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let page = self.page;
+        Resp::new(future::ready(client.call().map(|()| page)))
+    }
+}
+
+impl RetriableRequest for ListItems {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        true
+    }
+}
+
+impl PagedRequest for ListItems {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.page = response + 1;
+        self.page < 3
+    }
+}
+
+#[tokio::test]
+async fn arc_send() {
+    let client = Client::default();
+    let req = Arc::new(ListItems::default());
+    let cloned = req.cloned.clone();
+
+    let (a, b) = future::join(
+        req.clone().oneshot().send_once(&client),
+        req.clone().oneshot().send_once(&client),
+    )
+    .await;
+    assert_eq!(a.unwrap(), 0);
+    assert_eq!(b.unwrap(), 0);
+    assert_eq!(cloned.load(Ordering::SeqCst), 2);
+
+    let res = req.oneshot().send_once(&client).await;
+    assert_eq!(res.unwrap(), 0);
+    assert_eq!(cloned.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn arc_paginate() {
+    let client = Client::default();
+    let req = Arc::new(ListItems::default());
+
+    let pages: Vec<_> = req.clone().paginate(&client).try_collect().await.unwrap();
+    assert_eq!(pages, vec![0, 1, 2]);
+    assert_eq!(req.page, 0);
+    assert_eq!(req.cloned.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn rc_retry() {
+    let client = Client {
+        fail_until: 2,
+        ..Default::default()
+    };
+    let req = Rc::new(ListItems {
+        page: 7,
+        ..Default::default()
+    });
+    let mut backoff = ExponentialBackoff::default();
+    backoff.as_mut().initial_interval = Duration::from_millis(10);
+
+    let res = req
+        .clone()
+        .retry_with_backoff(backoff)
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 7);
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+    assert_eq!(req.cloned.load(Ordering::SeqCst), 1);
+}
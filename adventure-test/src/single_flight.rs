@@ -0,0 +1,152 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{future::join3, pin_mut, prelude::*};
+use tokio::time::delay_for;
+
+use adventure::prelude::*;
+use adventure::response::*;
+use adventure::single_flight::SingleFlight;
+
+#[derive(Default)]
+struct Client {
+    called: AtomicUsize,
+}
+
+type Resp = FutureResponseObj<'static, String, String>;
+
+#[derive(Clone)]
+struct DescribeService {
+    name: &'static str,
+}
+
+impl BaseRequest for DescribeService {
+    type Ok = String;
+    type Error = String;
+}
+
+impl Request<&Client> for DescribeService {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let i = client.called.fetch_add(1, Ordering::SeqCst);
+        let name = self.name;
+        Resp::new(async move {
+            delay_for(Duration::from_millis(50)).await;
+            if name.is_empty() {
+                Err(format!("not found ({})", i))
+            } else {
+                Ok(format!("{}#{}", name, i))
+            }
+        })
+    }
+}
+
+impl RequestKey for DescribeService {
+    type Key = &'static str;
+
+    fn key(&self) -> Self::Key {
+        self.name
+    }
+}
+
+type Group = SingleFlight<&'static str, Resp>;
+
+fn send<'a>(
+    name: &'static str,
+    group: &'a Group,
+    client: &'a Client,
+) -> impl Response<Ok = String, Error = String> + 'a {
+    let req = DescribeService { name }.coalesce(group);
+    pin_mut!(req);
+    req.send(client)
+}
+
+#[tokio::test]
+async fn single_flight_coalesce() {
+    let client = Client::default();
+    let group = Group::new();
+    let get = |name| send(name, &group, &client).into_future();
+
+    let (a, b, c) = join3(get("api"), get("api"), get("web")).await;
+    assert_eq!(a.unwrap(), "api#0");
+    assert_eq!(b.unwrap(), "api#0");
+    assert_eq!(c.unwrap(), "web#1");
+    assert!(group.is_empty());
+
+    assert_eq!(get("api").await.unwrap(), "api#2");
+    assert_eq!(client.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn single_flight_error() {
+    let client = Client::default();
+    let group = Group::new();
+    let get = || send("", &group, &client).into_future();
+
+    let (a, b) = future::join(get(), get()).await;
+    assert_eq!(a.unwrap_err(), "not found (0)");
+    assert_eq!(b.unwrap_err(), "not found (0)");
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn single_flight_leader_dropped() {
+    let client = Client::default();
+    let group = Group::new();
+    let get = || send("api", &group, &client).into_future();
+
+    let mut leader = get();
+    let follower = get();
+    assert!(futures::poll!(&mut leader).is_pending());
+    drop(leader);
+
+    assert_eq!(follower.await.unwrap(), "api#0");
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+    assert!(group.is_empty());
+}
+
+/// Coalesces the request of its service in the same group while sent.
+#[derive(Clone)]
+struct DescribeCluster {
+    group: Arc<Group>,
+}
+
+impl BaseRequest for DescribeCluster {
+    type Ok = String;
+    type Error = String;
+}
+
+impl Request<&Client> for DescribeCluster {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, client: &Client) -> Self::Response {
+        let service = DescribeService { name: "api" }.coalesce(self.group.clone());
+        pin_mut!(service);
+        Resp::new(service.send(client).map_ok(|s| format!("cluster({})", s)))
+    }
+}
+
+impl RequestKey for DescribeCluster {
+    type Key = &'static str;
+
+    fn key(&self) -> Self::Key {
+        "cluster"
+    }
+}
+
+#[tokio::test]
+async fn single_flight_nested() {
+    let client = Client::default();
+    let group = Arc::new(Group::new());
+    let req = DescribeCluster {
+        group: group.clone(),
+    }
+    .coalesce(group.clone());
+    pin_mut!(req);
+
+    assert_eq!(req.send(&client).await.unwrap(), "cluster(api#0)");
+    assert!(group.is_empty());
+}
@@ -0,0 +1,108 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::{pin_mut, prelude::*};
+
+use adventure::prelude::*;
+use adventure::response::*;
+use adventure::retry::{RetryError, Timer};
+
+#[derive(Debug, Default)]
+struct Hanging {
+    called: AtomicUsize,
+    hang_until: usize,
+}
+
+impl Clone for Hanging {
+    fn clone(&self) -> Self {
+        Hanging {
+            called: AtomicUsize::new(self.called.load(Ordering::SeqCst)),
+            hang_until: self.hang_until,
+        }
+    }
+}
+
+type Resp = FutureResponseObj<'static, usize, String>;
+
+impl BaseRequest for Hanging {
+    type Ok = usize;
+    type Error = String;
+}
+
+impl<C> Request<C> for Hanging {
+    type Response = Resp;
+
+    fn send(self: Pin<&mut Self>, _client: C) -> Self::Response {
+        let i = self.called.fetch_add(1, Ordering::SeqCst);
+        if i < self.hang_until {
+            Resp::new(future::pending())
+        } else {
+            Resp::new(future::ok(i))
+        }
+    }
+}
+
+impl RetriableRequest for Hanging {
+    fn should_retry(&self, _error: &Self::Error, _next_interval: Duration) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Default)]
+struct ImmediateTimer;
+
+impl Timer for ImmediateTimer {
+    type Delay = future::Ready<Result<(), RetryError>>;
+
+    fn expires_in(&mut self, _interval: Duration) -> Self::Delay {
+        future::ok(())
+    }
+}
+
+#[tokio::test]
+async fn timeout_ok() {
+    let req = Hanging::default().timeout(Duration::from_millis(100));
+    pin_mut!(req);
+
+    assert_eq!(req.send(()).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn timeout_elapsed() {
+    let req = Hanging {
+        hang_until: 1,
+        ..Default::default()
+    }
+    .timeout(Duration::from_millis(10));
+    pin_mut!(req);
+
+    let err = req.as_mut().send(()).await.unwrap_err();
+    assert!(err.is_elapsed());
+    assert_eq!(req.send(()).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn timeout_retry() {
+    let req = Hanging {
+        hang_until: 3,
+        ..Default::default()
+    }
+    .timeout(Duration::from_millis(10))
+    .retry();
+
+    assert_eq!(req.send_once(()).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn timeout_with_timer() {
+    let req = Hanging {
+        hang_until: 1,
+        ..Default::default()
+    }
+    .timeout_with_timer(ImmediateTimer, Duration::from_secs(60));
+    pin_mut!(req);
+
+    assert!(req.as_mut().send(()).await.unwrap_err().is_elapsed());
+    assert_eq!(req.send(()).await.unwrap(), 1);
+}
@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::prelude::*;
+use tower_service::Service;
+
+use adventure::oneshot::Oneshot;
+use adventure::prelude::*;
+use adventure::tower::{ClientService, ServiceRequest};
+
+use crate::fixture::{backoff, Client, ListItems};
+
+#[derive(Clone, Default)]
+struct Backend {
+    ready: Arc<AtomicUsize>,
+    called: Arc<AtomicUsize>,
+    fail_until: usize,
+}
+
+impl Service<ListItems> for Backend {
+    type Response = usize;
+    type Error = String;
+    type Future = future::Ready<Result<usize, String>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.ready.fetch_add(1, Ordering::SeqCst);
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: ListItems) -> Self::Future {
+        let i = self.called.fetch_add(1, Ordering::SeqCst);
+        if i < self.fail_until {
+            future::err(format!("{} failed", i))
+        } else {
+            future::ok(req.page)
+        }
+    }
+}
+
+#[tokio::test]
+async fn client_service() {
+    let client = Client::default();
+    let mut service = ClientService::new(&client);
+
+    future::poll_fn(|cx| Service::<Oneshot<ListItems>>::poll_ready(&mut service, cx))
+        .await
+        .unwrap();
+    let res = service.call(Oneshot::from(ListItems { page: 4 })).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(client.called.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn service_request_paginate() {
+    let backend = Backend::default();
+    let pages: Vec<_> = ServiceRequest::from(ListItems { page: 0 })
+        .paginate(backend.clone())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(pages, vec![0, 1, 2]);
+    assert_eq!(backend.ready.load(Ordering::SeqCst), 3);
+    assert_eq!(backend.called.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn service_request_retry() {
+    let backend = Backend {
+        fail_until: 2,
+        ..Default::default()
+    };
+    let res = ServiceRequest::from(ListItems { page: 7 })
+        .retry_with_backoff(backoff())
+        .send_once(backend.clone())
+        .await;
+    assert_eq!(res.unwrap(), 7);
+    assert_eq!(backend.called.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,67 @@
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+
+use futures::prelude::*;
+
+use adventure::map::MapOk;
+use adventure::prelude::*;
+
+use crate::fixture::{backoff, Client, ListItems};
+
+// combinators can be added by other crates in the same way.
+trait DoubledExt: RequestExt<Ok = usize> {
+    fn doubled(self) -> MapOk<Self, fn(usize) -> usize>
+    where
+        Self: Sized,
+    {
+        self.map_ok(|n| n * 2)
+    }
+}
+
+impl<R> DoubledExt for R where R: RequestExt<Ok = usize> {}
+
+#[tokio::test]
+async fn ext_on_pointers() {
+    let client = Client {
+        fail_until: 1,
+        ..Default::default()
+    };
+
+    let res = Box::new(ListItems { page: 1 })
+        .retry_with_backoff(backoff())
+        .map_ok(|n| n + 10)
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 11);
+    assert_eq!(client.called.load(Ordering::SeqCst), 2);
+
+    let mut req = ListItems { page: 0 };
+    let pages: Vec<_> = Pin::new(&mut req)
+        .paginate(&client)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(pages, vec![0, 1, 2]);
+    assert_eq!(req.page, 3);
+}
+
+#[tokio::test]
+async fn ext_repeat() {
+    let client = Client::default();
+    let req = Box::new(ListItems { page: 2 }.oneshot()).repeat();
+    futures::pin_mut!(req);
+    assert_eq!(req.as_mut().send(&client).await.unwrap(), 2);
+    assert_eq!(req.as_mut().send(&client).await.unwrap(), 2);
+    assert_eq!(client.called.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn ext_downstream() {
+    let client = Client::default();
+    let res = ListItems { page: 4 }
+        .doubled()
+        .oneshot()
+        .send_once(&client)
+        .await;
+    assert_eq!(res.unwrap(), 8);
+}
@@ -0,0 +1,343 @@
+//! An adaptor to send dependent requests in sequence.
+use core::fmt::{self, Display};
+use core::future::Future;
+#[cfg(not(feature = "std"))]
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// The second request built from the successful value of the first one,
+/// kept while it has not succeeded yet.
+#[cfg(feature = "std")]
+type Slot<T> = Arc<Mutex<Option<T>>>;
+
+/// The slot to keep the second request, and how to copy the request into it.
+#[cfg(feature = "std")]
+type KeepSecond<T> = (Slot<T>, fn(&T) -> T);
+
+#[cfg(feature = "std")]
+fn lock<T>(slot: &Slot<T>) -> MutexGuard<'_, Option<T>> {
+    slot.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Request for [`and_then`](crate::util::RequestExt::and_then) combinator.
+///
+/// The second request, built from the successful value of the first
+/// request, is kept until it succeeds. So sending this request again after
+/// a failure of the second request, e.g. by
+/// [`retry`](crate::util::RequestExt::retry), sends only a copy of the
+/// second request, without building it again. A clone of this request
+/// starts from the first request again.
+pub struct AndThen<R, F, U> {
+    inner: R,
+    f: F,
+    #[cfg(feature = "std")]
+    second: Slot<U>,
+    #[cfg(not(feature = "std"))]
+    second: PhantomData<fn() -> U>,
+}
+
+impl<R, F, U> AndThen<R, F, U> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(f: F);
+
+    pub(crate) fn new(req: R, f: F) -> Self {
+        AndThen {
+            inner: req,
+            f,
+            second: Default::default(),
+        }
+    }
+
+    /// Consumes this adaptor, returning the first request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, F, U> Clone for AndThen<R, F, U>
+where
+    R: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        AndThen::new(self.inner.clone(), self.f.clone())
+    }
+}
+
+impl<R, F, U> BaseRequest for AndThen<R, F, U>
+where
+    R: BaseRequest,
+    F: FnOnce(R::Ok) -> U,
+    U: BaseRequest,
+{
+    type Ok = U::Ok;
+    type Error = AndThenError<R::Error, U::Error>;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, F, U, C> OneshotRequest<C> for AndThen<R, F, U>
+where
+    R: OneshotRequest<C>,
+    F: FnOnce(R::Ok) -> U,
+    U: OneshotRequest<C>,
+    C: Clone,
+{
+    type Response = AndThenResponse<R::Response, F, U, C>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let first = self.inner.send_once(client.clone());
+        AndThenResponse::new(first, self.f, client)
+    }
+}
+
+impl<R, F, U, C> Request<C> for AndThen<R, F, U>
+where
+    R: Request<C>,
+    F: FnOnce(R::Ok) -> U + Clone,
+    U: OneshotRequest<C> + Clone,
+    C: Clone,
+{
+    type Response = AndThenResponse<R::Response, F, U, C>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        #[cfg(feature = "std")]
+        {
+            let second = lock(&self.second).clone();
+            if let Some(second) = second {
+                let second = second.send_once(client);
+                return AndThenResponse::resume(second, self.second.clone());
+            }
+        }
+
+        let f = self.as_mut().f().clone();
+        let first = self.as_mut().inner().send(client.clone());
+        let res = AndThenResponse::new(first, f, client);
+        #[cfg(feature = "std")]
+        let res = res.keep_second(self.second.clone());
+        res
+    }
+}
+
+impl<R, F, U> Unpin for AndThen<R, F, U> where R: Unpin {}
+
+/// Response for [`and_then`](crate::util::RequestExt::and_then) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct AndThenResponse<T, F, U, C>
+where
+    T: Response,
+    U: OneshotRequest<C>,
+{
+    first: Option<T>,
+    second: Option<U::Response>,
+    f: Option<F>,
+    client: Option<C>,
+    #[cfg(feature = "std")]
+    keep: Option<KeepSecond<U>>,
+}
+
+impl<T, F, U, C> AndThenResponse<T, F, U, C>
+where
+    T: Response,
+    U: OneshotRequest<C>,
+{
+    unsafe_pinned!(first: Option<T>);
+    unsafe_pinned!(second: Option<U::Response>);
+    unsafe_unpinned!(f: Option<F>);
+    unsafe_unpinned!(client: Option<C>);
+
+    fn new(first: T, f: F, client: C) -> Self {
+        AndThenResponse {
+            first: Some(first),
+            second: None,
+            f: Some(f),
+            client: Some(client),
+            #[cfg(feature = "std")]
+            keep: None,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn keep_second(self, slot: Slot<U>) -> Self
+    where
+        U: Clone,
+    {
+        AndThenResponse {
+            keep: Some((slot, Clone::clone)),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn resume(second: U::Response, slot: Slot<U>) -> Self
+    where
+        U: Clone,
+    {
+        AndThenResponse {
+            first: None,
+            second: Some(second),
+            f: None,
+            client: None,
+            keep: Some((slot, Clone::clone)),
+        }
+    }
+}
+
+impl<T, F, U, C> Unpin for AndThenResponse<T, F, U, C>
+where
+    T: Response + Unpin,
+    U: OneshotRequest<C>,
+    U::Response: Unpin,
+{
+}
+
+impl<T, F, U, C> Future for AndThenResponse<T, F, U, C>
+where
+    T: Response,
+    F: FnOnce(T::Ok) -> U,
+    U: OneshotRequest<C>,
+{
+    type Output = Result<U::Ok, AndThenError<T::Error, U::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(first) = self.as_mut().first().as_pin_mut() {
+            let ok = match first.try_poll(ctx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(ok)) => ok,
+                Poll::Ready(Err(e)) => {
+                    self.as_mut().first().set(None);
+                    return Poll::Ready(Err(AndThenError::First(e)));
+                }
+            };
+            self.as_mut().first().set(None);
+
+            let f = self
+                .as_mut()
+                .f()
+                .take()
+                .expect("the function is kept until the first response completes");
+            let client = self
+                .as_mut()
+                .client()
+                .take()
+                .expect("the client is kept until the first response completes");
+            let second = f(ok);
+
+            #[cfg(feature = "std")]
+            {
+                if let Some((slot, clone)) = &self.keep {
+                    *lock(slot) = Some(clone(&second));
+                }
+            }
+
+            let second = second.send_once(client);
+            self.as_mut().second().set(Some(second));
+        }
+
+        let res = match self.as_mut().second().as_pin_mut() {
+            Some(second) => match second.try_poll(ctx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(res) => res,
+            },
+            None => panic!("AndThenResponse polled after completion"),
+        };
+
+        #[cfg(feature = "std")]
+        {
+            if let (Ok(_), Some((slot, _))) = (&res, &self.keep) {
+                *lock(slot) = None;
+            }
+        }
+        Poll::Ready(res.map_err(AndThenError::Second))
+    }
+}
+
+/// Errors from either of requests chained by
+/// [`and_then`](crate::util::RequestExt::and_then) combinator.
+#[derive(Debug)]
+pub enum AndThenError<E1, E2> {
+    /// The first request has failed.
+    First(E1),
+    /// The first request has succeeded, but the second one has failed.
+    Second(E2),
+}
+
+impl<E> AndThenError<E, E> {
+    /// Returns the inner error, regardless of which request has failed.
+    pub fn into_inner(self) -> E {
+        match self {
+            AndThenError::First(e) => e,
+            AndThenError::Second(e) => e,
+        }
+    }
+}
+
+impl<E1: Display, E2: Display> Display for AndThenError<E1, E2> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AndThenError::First(e) => e.fmt(f),
+            AndThenError::Second(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E1, E2> StdError for AndThenError<E1, E2>
+where
+    E1: StdError + 'static,
+    E2: StdError + 'static,
+{
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            AndThenError::First(e) => Some(e),
+            AndThenError::Second(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    #[cfg(feature = "std")]
+    use super::lock;
+    use super::{AndThen, AndThenError};
+    use crate::retry::RetriableRequest;
+
+    impl<R, F, U> RetriableRequest for AndThen<R, F, U>
+    where
+        R: RetriableRequest,
+        F: FnOnce(R::Ok) -> U,
+        U: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            match error {
+                AndThenError::First(e) => self.inner.should_retry(e, next_interval),
+                #[cfg(feature = "std")]
+                AndThenError::Second(e) => match &*lock(&self.second) {
+                    Some(second) => second.should_retry(e, next_interval),
+                    None => false,
+                },
+                #[cfg(not(feature = "std"))]
+                AndThenError::Second(_) => false,
+            }
+        }
+    }
+}
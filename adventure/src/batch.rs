@@ -0,0 +1,241 @@
+//! Sending a number of requests concurrently.
+use alloc::collections::BTreeMap;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures::stream::{self, Fuse, FuturesUnordered, Stream, StreamExt};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::oneshot::OneshotRequest;
+use crate::request::BaseRequest;
+use crate::response::Response;
+
+type RequestResult<R> = Result<<R as BaseRequest>::Ok, <R as BaseRequest>::Error>;
+type Indexed<R> = (usize, RequestResult<R>);
+
+/// Send all of the given requests to the client concurrently.
+///
+/// Each item of the resulting stream is paired with the index of its
+/// originating request, and yielded as soon as its response is completed,
+/// unless [`ordered`](SendAll::ordered) is set.
+pub fn send_all<I, C>(requests: I, client: C) -> SendAll<stream::Iter<I::IntoIter>, C>
+where
+    I: IntoIterator,
+    I::Item: OneshotRequest<C>,
+    C: Clone,
+{
+    send_all_stream(stream::iter(requests), client)
+}
+
+/// Send all of requests from the given stream to the client concurrently.
+///
+/// See [`send_all`] for details.
+pub fn send_all_stream<S, C>(requests: S, client: C) -> SendAll<S, C>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C>,
+    C: Clone,
+{
+    SendAll {
+        requests: requests.fuse(),
+        client,
+        limit: None,
+        ordered: false,
+        fail_fast: false,
+        next_index: 0,
+        next_yield: 0,
+        in_flight: FuturesUnordered::new(),
+        completed: BTreeMap::new(),
+        failing: None,
+        failed: false,
+    }
+}
+
+/// Stream for [`send_all`] and [`send_all_stream`].
+#[must_use = "streams do nothing unless polled"]
+pub struct SendAll<S, C>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C>,
+{
+    requests: Fuse<S>,
+    client: C,
+    limit: Option<usize>,
+    ordered: bool,
+    fail_fast: bool,
+    next_index: usize,
+    next_yield: usize,
+    in_flight: FuturesUnordered<IndexedResponse<<S::Item as OneshotRequest<C>>::Response>>,
+    completed: BTreeMap<usize, RequestResult<S::Item>>,
+    failing: Option<usize>,
+    failed: bool,
+}
+
+impl<S, C> SendAll<S, C>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C>,
+    C: Clone,
+{
+    unsafe_pinned!(requests: Fuse<S>);
+    unsafe_unpinned!(in_flight: FuturesUnordered<IndexedResponse<<S::Item as OneshotRequest<C>>::Response>>);
+
+    /// Limits the number of requests to be in flight at the same time.
+    pub fn concurrency(self, limit: usize) -> Self {
+        assert!(limit > 0, "concurrency limit must be positive");
+        SendAll {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Yields the results in the same order of their originating requests.
+    pub fn ordered(self) -> Self {
+        SendAll {
+            ordered: true,
+            ..self
+        }
+    }
+
+    /// Stops sending requests after the first failure, and ends the stream
+    /// once the failure is yielded.
+    ///
+    /// If the results are [`ordered`](SendAll::ordered), the first failure
+    /// is the one of the earliest request, and the results of requests
+    /// before it are yielded first, waiting for their responses. Responses
+    /// of the later requests are dropped.
+    ///
+    /// Otherwise, the first failure is the one completed first, and the
+    /// other responses in flight are dropped at once.
+    ///
+    /// Without this, all of requests are sent regardless of failures.
+    pub fn fail_fast(self) -> Self {
+        SendAll {
+            fail_fast: true,
+            ..self
+        }
+    }
+
+    fn has_capacity(&self) -> bool {
+        match self.limit {
+            Some(limit) => self.in_flight.len() + self.completed.len() < limit,
+            None => true,
+        }
+    }
+
+    fn fill(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) {
+        while self.failing.is_none() && self.has_capacity() {
+            let request = match self.as_mut().requests().poll_next(ctx) {
+                Poll::Ready(Some(request)) => request,
+                _ => break,
+            };
+            let this = unsafe { self.as_mut().get_unchecked_mut() };
+            let response = request.send_once(this.client.clone());
+            this.in_flight.push(IndexedResponse {
+                index: this.next_index,
+                inner: response,
+            });
+            this.next_index += 1;
+        }
+    }
+
+    fn take_next(self: Pin<&mut Self>) -> Option<Indexed<S::Item>> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let result = this.completed.remove(&this.next_yield)?;
+        let index = this.next_yield;
+        this.next_yield += 1;
+        Some((index, result))
+    }
+
+    fn abort(self: Pin<&mut Self>) {
+        let this = unsafe { self.get_unchecked_mut() };
+        this.failed = true;
+        this.in_flight = FuturesUnordered::new();
+        this.completed.clear();
+    }
+}
+
+impl<S, C> Unpin for SendAll<S, C>
+where
+    S: Stream + Unpin,
+    S::Item: OneshotRequest<C>,
+{
+}
+
+impl<S, C> Stream for SendAll<S, C>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C>,
+    C: Clone,
+{
+    type Item = Indexed<S::Item>;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.failed {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            self.as_mut().fill(ctx);
+
+            if let Some(item) = self.as_mut().take_next() {
+                if self.failing == Some(item.0) {
+                    self.abort();
+                }
+                return Poll::Ready(Some(item));
+            }
+
+            let (index, result) = match self.as_mut().in_flight().poll_next_unpin(ctx) {
+                Poll::Ready(Some(item)) => item,
+                Poll::Ready(None) if self.requests.is_done() => return Poll::Ready(None),
+                _ => return Poll::Pending,
+            };
+
+            if !self.ordered {
+                if self.fail_fast && result.is_err() {
+                    self.abort();
+                }
+                return Poll::Ready(Some((index, result)));
+            }
+
+            let this = unsafe { self.as_mut().get_unchecked_mut() };
+            if let Some(failing) = this.failing {
+                if index > failing {
+                    continue;
+                }
+            }
+            if this.fail_fast && result.is_err() {
+                // responses of the later requests are not yielded anymore
+                this.completed.split_off(&index);
+                this.failing = Some(index);
+            }
+            this.completed.insert(index, result);
+        }
+    }
+}
+
+/// A response paired with the index of its originating request.
+#[must_use = "responses do nothing unless polled"]
+pub struct IndexedResponse<F> {
+    index: usize,
+    inner: F,
+}
+
+impl<F> IndexedResponse<F> {
+    unsafe_pinned!(inner: F);
+}
+
+impl<F: Unpin> Unpin for IndexedResponse<F> {}
+
+impl<F> Future for IndexedResponse<F>
+where
+    F: Response,
+{
+    type Output = (usize, Result<F::Ok, F::Error>);
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let index = self.index;
+        self.as_mut().inner().try_poll(ctx).map(|r| (index, r))
+    }
+}
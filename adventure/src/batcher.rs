@@ -0,0 +1,416 @@
+//! Coalescing individual requests into batch requests, like a dataloader.
+use core::fmt::{self, Display};
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::error::Error as StdError;
+
+use futures::channel::{mpsc, oneshot};
+use futures::stream::{FuturesUnordered, Stream};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::oneshot::OneshotRequest;
+use crate::request::BaseRequest;
+use crate::response::Response;
+use crate::retry::Timer;
+
+type BatchResult<B, C> = Result<
+    <<B as Batch<C>>::Request as BaseRequest>::Ok,
+    <<B as Batch<C>>::Request as BaseRequest>::Error,
+>;
+type Waiter<V, E> = oneshot::Sender<Result<V, BatchError<E>>>;
+type Item<B, C> = (
+    <B as Batch<C>>::Key,
+    Waiter<<B as Batch<C>>::Value, <B as Batch<C>>::Error>,
+);
+type Flown<B, C> = (
+    Vec<<B as Batch<C>>::Key>,
+    Vec<Waiter<<B as Batch<C>>::Value, <B as Batch<C>>::Error>>,
+    BatchResult<B, C>,
+);
+
+/// Describes how to send many single keys at once, like looking up a set of
+/// resources by their IDs, or deleting them in a single request.
+pub trait Batch<C> {
+    /// The type of single keys to be loaded.
+    type Key;
+    /// The type of values loaded for each key.
+    type Value;
+    /// The type of errors for each key.
+    type Error;
+    /// The type of batch requests.
+    type Request: OneshotRequest<C>;
+
+    /// Builds a batch request for the given keys.
+    fn batch(&self, keys: &[Self::Key]) -> Self::Request;
+
+    /// Splits the result of the batch request into the results of each key,
+    /// in the same order with the given keys.
+    ///
+    /// Keys without a result fail with [`BatchError::is_missing`].
+    fn split(
+        &self,
+        keys: &[Self::Key],
+        result: BatchResult<Self, C>,
+    ) -> Vec<Result<Self::Value, Self::Error>>;
+}
+
+/// Errors from [`Load`] responses.
+#[derive(Debug)]
+pub struct BatchError<E> {
+    inner: BatchErrorKind<E>,
+}
+
+#[derive(Debug)]
+enum BatchErrorKind<E> {
+    Inner(E),
+    Missing,
+    Closed,
+}
+
+impl<E: Display> Display for BatchError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use BatchErrorKind::*;
+        match &self.inner {
+            Inner(e) => e.fmt(f),
+            Missing => "Batch response has no result for the key".fmt(f),
+            Closed => "Batch driver has gone".fmt(f),
+        }
+    }
+}
+
+impl<E: StdError + 'static> StdError for BatchError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use BatchErrorKind::*;
+        match &self.inner {
+            Inner(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E> BatchError<E> {
+    /// Creates an error from the error of a single key.
+    pub fn from_err(e: E) -> Self {
+        BatchError {
+            inner: BatchErrorKind::Inner(e),
+        }
+    }
+
+    const fn missing() -> Self {
+        BatchError {
+            inner: BatchErrorKind::Missing,
+        }
+    }
+
+    const fn closed() -> Self {
+        BatchError {
+            inner: BatchErrorKind::Closed,
+        }
+    }
+
+    /// Returns a reference to the error of the key, if the error was
+    /// split from the batch response.
+    pub fn as_inner(&self) -> Option<&E> {
+        if let BatchErrorKind::Inner(e) = &self.inner {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the error, returning the error of the key if the error was
+    /// split from the batch response.
+    pub fn into_inner(self) -> Option<E> {
+        if let BatchErrorKind::Inner(e) = self.inner {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the batch response had no result for the key.
+    pub fn is_missing(&self) -> bool {
+        if let BatchErrorKind::Missing = &self.inner {
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if the [`BatchDriver`] was dropped before the key was
+    /// sent.
+    pub fn is_closed(&self) -> bool {
+        if let BatchErrorKind::Closed = &self.inner {
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A handle to load values of single keys, which are sent in batches by the
+/// corresponding [`BatchDriver`].
+pub struct Batcher<K, V, E> {
+    sender: mpsc::UnboundedSender<(K, Waiter<V, E>)>,
+}
+
+impl<K, V, E> Clone for Batcher<K, V, E> {
+    fn clone(&self) -> Self {
+        Batcher {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<K, V, E> Batcher<K, V, E> {
+    /// Creates a batcher and its driver, which sends batch requests built by
+    /// `batch` to the client.
+    ///
+    /// The driver should be spawned or polled along with the responses of
+    /// the batcher. It completes after all of the batchers are dropped and
+    /// the keys sent so far are loaded.
+    ///
+    /// By default, a batch request is sent with up to 100 keys, or the keys
+    /// collected for 10 milliseconds since the first one.
+    pub fn new<B, C, T>(batch: B, client: C, timer: T) -> (Self, BatchDriver<B, C, T>)
+    where
+        B: Batch<C, Key = K, Value = V, Error = E>,
+        C: Clone,
+        T: Timer,
+    {
+        let (sender, receiver) = mpsc::unbounded();
+        let driver = BatchDriver {
+            batch,
+            client,
+            timer,
+            max_size: 100,
+            window: Duration::from_millis(10),
+            receiver: Some(receiver),
+            pending: Vec::new(),
+            delay: None,
+            in_flight: FuturesUnordered::new(),
+        };
+        (Batcher { sender }, driver)
+    }
+
+    /// Returns a response to the value of the given key, which is sent along
+    /// with other keys.
+    pub fn load(&self, key: K) -> Load<V, E> {
+        let (waiter, receiver) = oneshot::channel();
+        // if the driver is gone, the receiver is cancelled.
+        let _ = self.sender.unbounded_send((key, waiter));
+        Load { receiver }
+    }
+}
+
+/// Response for [`Batcher::load`].
+#[must_use = "responses do nothing unless polled"]
+pub struct Load<V, E> {
+    receiver: oneshot::Receiver<Result<V, BatchError<E>>>,
+}
+
+impl<V, E> Future for Load<V, E> {
+    type Output = Result<V, BatchError<E>>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.receiver).poll(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(BatchError::closed())),
+        }
+    }
+}
+
+/// A future to collect keys from [`Batcher`]s and to send batch requests.
+#[must_use = "futures do nothing unless polled"]
+pub struct BatchDriver<B, C, T>
+where
+    B: Batch<C>,
+    T: Timer,
+{
+    batch: B,
+    client: C,
+    timer: T,
+    max_size: usize,
+    window: Duration,
+    receiver: Option<mpsc::UnboundedReceiver<Item<B, C>>>,
+    pending: Vec<Item<B, C>>,
+    delay: Option<T::Delay>,
+    in_flight: FuturesUnordered<Flight<B, C>>,
+}
+
+impl<B, C, T> BatchDriver<B, C, T>
+where
+    B: Batch<C>,
+    T: Timer,
+{
+    unsafe_unpinned!(timer: T);
+    unsafe_unpinned!(receiver: Option<mpsc::UnboundedReceiver<Item<B, C>>>);
+    unsafe_unpinned!(pending: Vec<Item<B, C>>);
+    unsafe_pinned!(delay: Option<T::Delay>);
+    unsafe_unpinned!(in_flight: FuturesUnordered<Flight<B, C>>);
+
+    /// Sets the maximum number of keys in a batch request.
+    pub fn with_max_size(self, max_size: usize) -> Self {
+        assert!(max_size > 0, "max_size must be positive");
+        BatchDriver { max_size, ..self }
+    }
+
+    /// Sets how long to wait for more keys since the first one of a batch.
+    pub fn with_window(self, window: Duration) -> Self {
+        BatchDriver { window, ..self }
+    }
+}
+
+impl<B, C, T> Unpin for BatchDriver<B, C, T>
+where
+    B: Batch<C>,
+    T: Timer,
+    T::Delay: Unpin,
+{
+}
+
+impl<B, C, T> BatchDriver<B, C, T>
+where
+    B: Batch<C>,
+    C: Clone,
+    T: Timer,
+{
+    fn flush(mut self: Pin<&mut Self>) {
+        let size = self.max_size.min(self.pending.len());
+        let rest = self.as_mut().pending().split_off(size);
+        let pending = mem::replace(self.as_mut().pending(), rest);
+        let (keys, waiters): (Vec<_>, Vec<_>) = pending.into_iter().unzip();
+
+        let client = self.client.clone();
+        let response = self.batch.batch(&keys).send_once(client);
+        // the rest of keys wait for a new window.
+        self.as_mut().delay().set(None);
+        self.as_mut().in_flight().push(Flight {
+            response,
+            keys: Some(keys),
+            waiters: Some(waiters),
+        });
+    }
+
+    fn poll_impl(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<()> {
+        while let Some(receiver) = self.as_mut().receiver() {
+            match Pin::new(receiver).poll_next(ctx) {
+                Poll::Ready(Some(item)) => {
+                    self.as_mut().pending().push(item);
+                    if self.pending.len() >= self.max_size {
+                        self.as_mut().flush();
+                    }
+                }
+                Poll::Ready(None) => {
+                    *self.as_mut().receiver() = None;
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        loop {
+            if self.pending.is_empty() {
+                self.as_mut().delay().set(None);
+                break;
+            }
+            if self.receiver.is_none() {
+                self.as_mut().flush();
+                continue;
+            }
+            if self.delay.is_none() {
+                let window = self.window;
+                let delay = self.as_mut().timer().expires_in(window);
+                self.as_mut().delay().set(Some(delay));
+            }
+            let delay = self
+                .as_mut()
+                .delay()
+                .as_pin_mut()
+                .expect("the delay has been set above");
+            match delay.try_poll(ctx) {
+                // errors of the timer are ignored, not to lose the keys.
+                Poll::Ready(_) => self.as_mut().flush(),
+                Poll::Pending => break,
+            }
+        }
+
+        while let Poll::Ready(Some((keys, waiters, result))) =
+            Pin::new(self.as_mut().in_flight()).poll_next(ctx)
+        {
+            let mut results = self.batch.split(&keys, result).into_iter();
+            for waiter in waiters {
+                let result = match results.next() {
+                    Some(result) => result.map_err(BatchError::from_err),
+                    None => Err(BatchError::missing()),
+                };
+                let _ = waiter.send(result);
+            }
+        }
+
+        if self.receiver.is_none() && self.pending.is_empty() && self.in_flight.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<B, C, T> Future for BatchDriver<B, C, T>
+where
+    B: Batch<C>,
+    C: Clone,
+    T: Timer,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_impl(ctx)
+    }
+}
+
+struct Flight<B, C>
+where
+    B: Batch<C>,
+{
+    response: <B::Request as OneshotRequest<C>>::Response,
+    keys: Option<Vec<B::Key>>,
+    waiters: Option<Vec<Waiter<B::Value, B::Error>>>,
+}
+
+impl<B, C> Flight<B, C>
+where
+    B: Batch<C>,
+{
+    unsafe_pinned!(response: <B::Request as OneshotRequest<C>>::Response);
+    unsafe_unpinned!(keys: Option<Vec<B::Key>>);
+    unsafe_unpinned!(waiters: Option<Vec<Waiter<B::Value, B::Error>>>);
+}
+
+impl<B, C> Future for Flight<B, C>
+where
+    B: Batch<C>,
+{
+    type Output = Flown<B, C>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = match self.as_mut().response().try_poll(ctx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(result) => result,
+        };
+        let keys = self
+            .as_mut()
+            .keys()
+            .take()
+            .expect("Flight polled after completion");
+        let waiters = self
+            .as_mut()
+            .waiters()
+            .take()
+            .expect("Flight polled after completion");
+        Poll::Ready((keys, waiters, result))
+    }
+}
@@ -0,0 +1,53 @@
+//! Blocking counterparts of the asynchronous constructs, for synchronous
+//! callers without any async runtime.
+//!
+//! Along with this module, `blocking` feature provides
+//! [`send_blocking`](crate::util::OneshotRequestExt::send_blocking) to send
+//! a request, and
+//! [`retry_blocking`](crate::util::RequestExt::retry_blocking) which
+//! waits for the next attempt with [`ThreadTimer`](crate::retry::ThreadTimer).
+use futures::executor::block_on;
+use futures::stream::StreamExt;
+
+use crate::paginator::{PagedRequest, Paginator};
+use crate::request::Request;
+
+/// An iterator over the pages from the request, returned by
+/// [`paginate_blocking`](crate::util::RequestExt::paginate_blocking).
+pub struct BlockingPaginator<C, R>
+where
+    R: PagedRequest + Request<C>,
+{
+    inner: Paginator<C, R>,
+}
+
+impl<C, R> BlockingPaginator<C, R>
+where
+    R: PagedRequest + Request<C>,
+{
+    /// Consumes this iterator, returning the underlying stream.
+    pub fn into_inner(self) -> Paginator<C, R> {
+        self.inner
+    }
+}
+
+impl<C, R> From<Paginator<C, R>> for BlockingPaginator<C, R>
+where
+    R: PagedRequest + Request<C>,
+{
+    fn from(paginator: Paginator<C, R>) -> Self {
+        BlockingPaginator { inner: paginator }
+    }
+}
+
+impl<C, R> Iterator for BlockingPaginator<C, R>
+where
+    C: Clone + Unpin,
+    R: PagedRequest + Request<C> + Unpin,
+{
+    type Item = Result<R::Ok, R::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.inner.next())
+    }
+}
@@ -0,0 +1,449 @@
+//! Type-erased requests, to put requests of different types together.
+//!
+//! The responses of boxed requests are boxed as well, into
+//! [`LocalFutureResponseObj`] or [`FutureResponseObj`] respectively.
+use alloc::boxed::Box;
+use core::pin::Pin;
+#[cfg(feature = "backoff")]
+use core::time::Duration;
+
+use futures::future::TryFutureExt;
+
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::paginator::PagedRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::{FutureResponseObj, LocalFutureResponseObj};
+#[cfg(feature = "backoff")]
+use crate::retry::RetriableRequest;
+
+trait DynRequest<C, T, E, F> {
+    fn send_dyn(self: Pin<&mut Self>, client: C) -> F;
+
+    fn advance_dyn(self: Pin<&mut Self>, response: &T) -> bool;
+
+    fn extensions_dyn(&self) -> Option<&Extensions>;
+
+    #[cfg(feature = "backoff")]
+    fn should_retry_dyn(&self, error: &E, next_interval: Duration) -> bool;
+}
+
+type Advance<R> = fn(&mut R, &<R as BaseRequest>::Ok) -> bool;
+
+#[cfg(feature = "backoff")]
+type ShouldRetry<R> = fn(&R, &<R as BaseRequest>::Error, Duration) -> bool;
+
+struct Erased<R>
+where
+    R: BaseRequest,
+{
+    inner: R,
+    advance: Option<Advance<R>>,
+    #[cfg(feature = "backoff")]
+    should_retry: Option<ShouldRetry<R>>,
+}
+
+impl<R> Erased<R>
+where
+    R: BaseRequest,
+{
+    fn new(req: R) -> Self {
+        Erased {
+            inner: req,
+            advance: None,
+            #[cfg(feature = "backoff")]
+            should_retry: None,
+        }
+    }
+
+    fn paged(mut self) -> Self
+    where
+        R: PagedRequest + Unpin,
+    {
+        self.advance = Some(R::advance);
+        self
+    }
+
+    #[cfg(feature = "backoff")]
+    fn retriable(mut self) -> Self
+    where
+        R: RetriableRequest,
+    {
+        self.should_retry = Some(R::should_retry);
+        self
+    }
+
+    fn inner(self: Pin<&mut Self>) -> Pin<&mut R> {
+        unsafe { self.map_unchecked_mut(|this| &mut this.inner) }
+    }
+
+    fn advance(self: Pin<&mut Self>, response: &R::Ok) -> bool {
+        // `advance` is only set if `R` is `Unpin`.
+        let this = unsafe { self.get_unchecked_mut() };
+        match this.advance {
+            Some(advance) => advance(&mut this.inner, response),
+            None => false,
+        }
+    }
+
+    #[cfg(feature = "backoff")]
+    fn should_retry(&self, error: &R::Error, next_interval: Duration) -> bool {
+        match self.should_retry {
+            Some(should_retry) => should_retry(&self.inner, error, next_interval),
+            None => false,
+        }
+    }
+}
+
+impl<'a, R, C> DynRequest<C, R::Ok, R::Error, LocalFutureResponseObj<'a, R::Ok, R::Error>>
+    for Erased<R>
+where
+    R: Request<C>,
+    R::Response: 'a,
+{
+    fn send_dyn(self: Pin<&mut Self>, client: C) -> LocalFutureResponseObj<'a, R::Ok, R::Error> {
+        LocalFutureResponseObj::new(self.inner().send(client).into_future())
+    }
+
+    fn advance_dyn(self: Pin<&mut Self>, response: &R::Ok) -> bool {
+        self.advance(response)
+    }
+
+    fn extensions_dyn(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+
+    #[cfg(feature = "backoff")]
+    fn should_retry_dyn(&self, error: &R::Error, next_interval: Duration) -> bool {
+        self.should_retry(error, next_interval)
+    }
+}
+
+impl<'a, R, C> DynRequest<C, R::Ok, R::Error, FutureResponseObj<'a, R::Ok, R::Error>> for Erased<R>
+where
+    R: Request<C>,
+    R::Response: Send + 'a,
+{
+    fn send_dyn(self: Pin<&mut Self>, client: C) -> FutureResponseObj<'a, R::Ok, R::Error> {
+        FutureResponseObj::new(self.inner().send(client).into_future())
+    }
+
+    fn advance_dyn(self: Pin<&mut Self>, response: &R::Ok) -> bool {
+        self.advance(response)
+    }
+
+    fn extensions_dyn(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+
+    #[cfg(feature = "backoff")]
+    fn should_retry_dyn(&self, error: &R::Error, next_interval: Duration) -> bool {
+        self.should_retry(error, next_interval)
+    }
+}
+
+type LocalDynRequest<'a, C, T, E> = dyn DynRequest<C, T, E, LocalFutureResponseObj<'a, T, E>> + 'a;
+
+type SendDynRequest<'a, C, T, E> = dyn DynRequest<C, T, E, FutureResponseObj<'a, T, E>> + Send + 'a;
+
+/// A [`Request`] wrapping a trait object of requests.
+///
+/// A boxed request always implements [`PagedRequest`] and
+/// `RetriableRequest`, but forwards them to the inner request only if it has
+/// been constructed with the corresponding capability, like
+/// [`LocalBoxRequest::paged`]. Otherwise, `advance` and `should_retry`
+/// return `false`, so it consists of a single page, and is never retried.
+pub struct LocalBoxRequest<'a, C, T, E> {
+    inner: Pin<Box<LocalDynRequest<'a, C, T, E>>>,
+}
+
+impl<'a, C, T, E> LocalBoxRequest<'a, C, T, E> {
+    pub fn new<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + 'a,
+        R::Response: 'a,
+    {
+        LocalBoxRequest::from_erased(Erased::new(req))
+    }
+
+    /// Boxes the request, keeping its [`PagedRequest`] implementation.
+    pub fn paged<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + PagedRequest + Unpin + 'a,
+        R::Response: 'a,
+    {
+        LocalBoxRequest::from_erased(Erased::new(req).paged())
+    }
+
+    /// Boxes the request, keeping its `RetriableRequest` implementation.
+    #[cfg(feature = "backoff")]
+    pub fn retriable<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + RetriableRequest + 'a,
+        R::Response: 'a,
+    {
+        LocalBoxRequest::from_erased(Erased::new(req).retriable())
+    }
+
+    /// Boxes the request, keeping both of its [`PagedRequest`] and
+    /// `RetriableRequest` implementations.
+    #[cfg(feature = "backoff")]
+    pub fn paged_retriable<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + PagedRequest + RetriableRequest + Unpin + 'a,
+        R::Response: 'a,
+    {
+        LocalBoxRequest::from_erased(Erased::new(req).paged().retriable())
+    }
+
+    fn from_erased<R>(erased: Erased<R>) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + 'a,
+        R::Response: 'a,
+    {
+        LocalBoxRequest {
+            inner: Box::pin(erased),
+        }
+    }
+}
+
+impl<'a, C, T, E> BaseRequest for LocalBoxRequest<'a, C, T, E> {
+    type Ok = T;
+    type Error = E;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions_dyn()
+    }
+}
+
+impl<'a, C, T, E> OneshotRequest<C> for LocalBoxRequest<'a, C, T, E> {
+    type Response = LocalFutureResponseObj<'a, T, E>;
+
+    fn send_once(mut self, client: C) -> Self::Response {
+        self.inner.as_mut().send_dyn(client)
+    }
+}
+
+impl<'a, C, T, E> Request<C> for LocalBoxRequest<'a, C, T, E> {
+    type Response = LocalFutureResponseObj<'a, T, E>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        self.inner.as_mut().send_dyn(client)
+    }
+}
+
+impl<'a, C, T, E> PagedRequest for LocalBoxRequest<'a, C, T, E> {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.inner.as_mut().advance_dyn(response)
+    }
+}
+
+#[cfg(feature = "backoff")]
+impl<'a, C, T, E> RetriableRequest for LocalBoxRequest<'a, C, T, E> {
+    fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+        self.inner.should_retry_dyn(error, next_interval)
+    }
+}
+
+/// A [`Request`] wrapping a trait object of requests, which can be sent to
+/// other threads.
+///
+/// A boxed request always implements [`PagedRequest`] and
+/// `RetriableRequest`, but forwards them to the inner request only if it has
+/// been constructed with the corresponding capability, like
+/// [`BoxRequest::paged`]. Otherwise, `advance` and `should_retry` return
+/// `false`, so it consists of a single page, and is never retried.
+pub struct BoxRequest<'a, C, T, E> {
+    inner: Pin<Box<SendDynRequest<'a, C, T, E>>>,
+}
+
+impl<'a, C, T, E> BoxRequest<'a, C, T, E> {
+    pub fn new<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + Send + 'a,
+        R::Response: Send + 'a,
+    {
+        BoxRequest::from_erased(Erased::new(req))
+    }
+
+    /// Boxes the request, keeping its [`PagedRequest`] implementation.
+    pub fn paged<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + PagedRequest + Send + Unpin + 'a,
+        R::Response: Send + 'a,
+    {
+        BoxRequest::from_erased(Erased::new(req).paged())
+    }
+
+    /// Boxes the request, keeping its `RetriableRequest` implementation.
+    #[cfg(feature = "backoff")]
+    pub fn retriable<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + RetriableRequest + Send + 'a,
+        R::Response: Send + 'a,
+    {
+        BoxRequest::from_erased(Erased::new(req).retriable())
+    }
+
+    /// Boxes the request, keeping both of its [`PagedRequest`] and
+    /// `RetriableRequest` implementations.
+    #[cfg(feature = "backoff")]
+    pub fn paged_retriable<R>(req: R) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + PagedRequest + RetriableRequest + Send + Unpin + 'a,
+        R::Response: Send + 'a,
+    {
+        BoxRequest::from_erased(Erased::new(req).paged().retriable())
+    }
+
+    fn from_erased<R>(erased: Erased<R>) -> Self
+    where
+        R: Request<C, Ok = T, Error = E> + Send + 'a,
+        R::Response: Send + 'a,
+    {
+        BoxRequest {
+            inner: Box::pin(erased),
+        }
+    }
+}
+
+impl<'a, C, T, E> BaseRequest for BoxRequest<'a, C, T, E> {
+    type Ok = T;
+    type Error = E;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions_dyn()
+    }
+}
+
+impl<'a, C, T, E> OneshotRequest<C> for BoxRequest<'a, C, T, E> {
+    type Response = FutureResponseObj<'a, T, E>;
+
+    fn send_once(mut self, client: C) -> Self::Response {
+        self.inner.as_mut().send_dyn(client)
+    }
+}
+
+impl<'a, C, T, E> Request<C> for BoxRequest<'a, C, T, E> {
+    type Response = FutureResponseObj<'a, T, E>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        self.inner.as_mut().send_dyn(client)
+    }
+}
+
+impl<'a, C, T, E> PagedRequest for BoxRequest<'a, C, T, E> {
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.inner.as_mut().advance_dyn(response)
+    }
+}
+
+#[cfg(feature = "backoff")]
+impl<'a, C, T, E> RetriableRequest for BoxRequest<'a, C, T, E> {
+    fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+        self.inner.should_retry_dyn(error, next_interval)
+    }
+}
+
+trait DynOneshotRequest<C, F> {
+    fn send_once_dyn(self: Box<Self>, client: C) -> F;
+
+    fn extensions_dyn(&self) -> Option<&Extensions>;
+}
+
+impl<'a, R, C> DynOneshotRequest<C, LocalFutureResponseObj<'a, R::Ok, R::Error>> for R
+where
+    R: OneshotRequest<C>,
+    R::Response: 'a,
+{
+    fn send_once_dyn(self: Box<Self>, client: C) -> LocalFutureResponseObj<'a, R::Ok, R::Error> {
+        LocalFutureResponseObj::new((*self).send_once(client).into_future())
+    }
+
+    fn extensions_dyn(&self) -> Option<&Extensions> {
+        self.extensions()
+    }
+}
+
+impl<'a, R, C> DynOneshotRequest<C, FutureResponseObj<'a, R::Ok, R::Error>> for R
+where
+    R: OneshotRequest<C>,
+    R::Response: Send + 'a,
+{
+    fn send_once_dyn(self: Box<Self>, client: C) -> FutureResponseObj<'a, R::Ok, R::Error> {
+        FutureResponseObj::new((*self).send_once(client).into_future())
+    }
+
+    fn extensions_dyn(&self) -> Option<&Extensions> {
+        self.extensions()
+    }
+}
+
+/// An [`OneshotRequest`] wrapping a trait object of requests.
+pub struct LocalBoxOneshotRequest<'a, C, T, E> {
+    inner: Box<dyn DynOneshotRequest<C, LocalFutureResponseObj<'a, T, E>> + 'a>,
+}
+
+impl<'a, C, T, E> LocalBoxOneshotRequest<'a, C, T, E> {
+    pub fn new<R>(req: R) -> Self
+    where
+        R: OneshotRequest<C, Ok = T, Error = E> + 'a,
+        R::Response: 'a,
+    {
+        LocalBoxOneshotRequest {
+            inner: Box::new(req),
+        }
+    }
+}
+
+impl<'a, C, T, E> BaseRequest for LocalBoxOneshotRequest<'a, C, T, E> {
+    type Ok = T;
+    type Error = E;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions_dyn()
+    }
+}
+
+impl<'a, C, T, E> OneshotRequest<C> for LocalBoxOneshotRequest<'a, C, T, E> {
+    type Response = LocalFutureResponseObj<'a, T, E>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        self.inner.send_once_dyn(client)
+    }
+}
+
+/// An [`OneshotRequest`] wrapping a trait object of requests, which can be
+/// sent to other threads.
+pub struct BoxOneshotRequest<'a, C, T, E> {
+    inner: Box<dyn DynOneshotRequest<C, FutureResponseObj<'a, T, E>> + Send + 'a>,
+}
+
+impl<'a, C, T, E> BoxOneshotRequest<'a, C, T, E> {
+    pub fn new<R>(req: R) -> Self
+    where
+        R: OneshotRequest<C, Ok = T, Error = E> + Send + 'a,
+        R::Response: Send + 'a,
+    {
+        BoxOneshotRequest {
+            inner: Box::new(req),
+        }
+    }
+}
+
+impl<'a, C, T, E> BaseRequest for BoxOneshotRequest<'a, C, T, E> {
+    type Ok = T;
+    type Error = E;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions_dyn()
+    }
+}
+
+impl<'a, C, T, E> OneshotRequest<C> for BoxOneshotRequest<'a, C, T, E> {
+    type Response = FutureResponseObj<'a, T, E>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        self.inner.send_once_dyn(client)
+    }
+}
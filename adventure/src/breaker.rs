@@ -0,0 +1,506 @@
+//! Circuit breakers, to stop sending requests to failing services for a while.
+use core::fmt::{self, Display};
+use core::future::Future;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::error::Error as StdError;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// A predicate to decide whether the error should be counted as a failure by
+/// the [`CircuitBreaker`].
+///
+/// Errors not counted as failures, like ones caused by invalid requests, are
+/// regarded as successful responses, because the service has responded.
+///
+/// [`RetrialPredicate`](crate::retry::RetrialPredicate) is not reused for
+/// this, because it also needs the request and the interval until the next
+/// attempt, which are not available when a response is recorded, and it is
+/// only provided with the `backoff` feature.
+pub trait BreakerPredicate<E> {
+    fn is_failure(&self, err: &E) -> bool;
+}
+
+impl<F, E> BreakerPredicate<E> for F
+where
+    F: Fn(&E) -> bool,
+{
+    fn is_failure(&self, err: &E) -> bool {
+        (self)(err)
+    }
+}
+
+impl<E> BreakerPredicate<E> for () {
+    fn is_failure(&self, _err: &E) -> bool {
+        true
+    }
+}
+
+/// A source of the current time, to measure the cool-down of
+/// [`CircuitBreaker`], the lifetime of entries in
+/// [`LruCache`](crate::cache::LruCache), and the refill of
+/// [`TokenBucket`](crate::rate_limit::TokenBucket).
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// A [`Clock`] reading [`Instant::now`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// States of a [`CircuitBreaker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests are sent as usual.
+    Closed,
+    /// Requests fail immediately without being sent.
+    Open,
+    /// A trial request is allowed, to decide whether to close the breaker.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        consecutive_failures: u32,
+        outcomes: VecDeque<bool>,
+    },
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        next_trial: Instant,
+    },
+}
+
+impl State {
+    fn closed() -> Self {
+        State::Closed {
+            consecutive_failures: 0,
+            outcomes: VecDeque::new(),
+        }
+    }
+}
+
+/// A circuit breaker, which can be shared by many requests with
+/// [`with_breaker`](crate::util::RequestExt::with_breaker).
+///
+/// By default, the breaker opens after 5 consecutive failures, and allows
+/// a trial request after a cool-down of 30 seconds. The breaker is closed
+/// again if the trial has succeeded, or opens again otherwise.
+///
+/// The cool-down is measured by a [`Clock`], which reads the system time
+/// by default.
+#[derive(Debug)]
+pub struct CircuitBreaker<K = SystemClock> {
+    consecutive_failures: u32,
+    failure_ratio: Option<(f64, usize)>,
+    cool_down: Duration,
+    clock: K,
+    state: Mutex<State>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker::with_clock(SystemClock)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<K> CircuitBreaker<K>
+where
+    K: Clock,
+{
+    /// Creates a breaker measuring its cool-down with the given clock.
+    pub fn with_clock(clock: K) -> Self {
+        CircuitBreaker {
+            consecutive_failures: 5,
+            failure_ratio: None,
+            cool_down: Duration::from_secs(30),
+            clock,
+            state: Mutex::new(State::closed()),
+        }
+    }
+
+    /// Opens the breaker after the given number of consecutive failures.
+    /// `0` disables this condition.
+    pub fn with_consecutive_failures(self, threshold: u32) -> Self {
+        CircuitBreaker {
+            consecutive_failures: threshold,
+            ..self
+        }
+    }
+
+    /// Opens the breaker if the ratio of failures among the last `window`
+    /// responses has reached the given ratio.
+    pub fn with_failure_ratio(self, ratio: f64, window: usize) -> Self {
+        assert!(window > 0, "window must be positive");
+        CircuitBreaker {
+            failure_ratio: Some((ratio, window)),
+            ..self
+        }
+    }
+
+    /// Sets how long the breaker stays open before allowing a trial request.
+    pub fn with_cool_down(self, cool_down: Duration) -> Self {
+        CircuitBreaker { cool_down, ..self }
+    }
+
+    /// Returns the current state of this breaker.
+    pub fn state(&self) -> BreakerState {
+        match *self.lock() {
+            State::Closed { .. } => BreakerState::Closed,
+            State::Open { until } if until > self.clock.now() => BreakerState::Open,
+            State::Open { .. } | State::HalfOpen { .. } => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Closes the breaker, forgetting all of responses recorded so far.
+    pub fn reset(&self) {
+        *self.lock() = State::closed();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn try_acquire(&self) -> bool {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } | State::HalfOpen { next_trial: until } if until <= now => {
+                // if the trial is dropped without its result, another one is
+                // allowed after the cool-down.
+                *state = State::HalfOpen {
+                    next_trial: now + self.cool_down,
+                };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => false,
+        }
+    }
+
+    pub(crate) fn record(&self, failed: bool) {
+        let mut state = self.lock();
+        let open = match &mut *state {
+            State::Closed {
+                consecutive_failures,
+                outcomes,
+            } => {
+                if failed {
+                    *consecutive_failures += 1;
+                } else {
+                    *consecutive_failures = 0;
+                }
+                let mut open = self.consecutive_failures > 0
+                    && *consecutive_failures >= self.consecutive_failures;
+                if let Some((ratio, window)) = self.failure_ratio {
+                    if outcomes.len() == window {
+                        outcomes.pop_front();
+                    }
+                    outcomes.push_back(failed);
+                    if outcomes.len() == window {
+                        let failures = outcomes.iter().filter(|&&f| f).count();
+                        open |= failures as f64 >= ratio * window as f64;
+                    }
+                }
+                open
+            }
+            State::HalfOpen { .. } if !failed => {
+                *state = State::closed();
+                false
+            }
+            State::HalfOpen { .. } => true,
+            // responses sent before opening the breaker
+            State::Open { .. } => false,
+        };
+        if open {
+            *state = State::Open {
+                until: self.clock.now() + self.cool_down,
+            };
+        }
+    }
+}
+
+/// Errors from requests guarded by the [`CircuitBreaker`].
+#[derive(Debug)]
+pub struct BreakerError<E> {
+    inner: BreakerErrorKind<E>,
+}
+
+#[derive(Debug)]
+enum BreakerErrorKind<E> {
+    Inner(E),
+    Open,
+}
+
+impl<E: Display> Display for BreakerError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use BreakerErrorKind::*;
+        match &self.inner {
+            Inner(e) => e.fmt(f),
+            Open => "Circuit breaker is open".fmt(f),
+        }
+    }
+}
+
+impl<E: StdError + 'static> StdError for BreakerError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        use BreakerErrorKind::*;
+        match &self.inner {
+            Inner(e) => Some(e),
+            Open => None,
+        }
+    }
+}
+
+impl<E> BreakerError<E> {
+    pub fn from_err(e: E) -> Self {
+        BreakerError {
+            inner: BreakerErrorKind::Inner(e),
+        }
+    }
+
+    pub(crate) const fn open() -> Self {
+        BreakerError {
+            inner: BreakerErrorKind::Open,
+        }
+    }
+
+    pub fn as_inner(&self) -> Option<&E> {
+        use BreakerErrorKind::*;
+        match &self.inner {
+            Inner(e) => Some(e),
+            Open => None,
+        }
+    }
+
+    pub fn into_inner(self) -> Option<E> {
+        use BreakerErrorKind::*;
+        match self.inner {
+            Inner(e) => Some(e),
+            Open => None,
+        }
+    }
+
+    pub fn is_inner(&self) -> bool {
+        self.as_inner().is_some()
+    }
+
+    /// Returns `true` if the request was not sent because the breaker was open.
+    pub fn is_open(&self) -> bool {
+        self.as_inner().is_none()
+    }
+}
+
+/// Request for [`with_breaker`](crate::util::RequestExt::with_breaker)
+/// combinator.
+#[derive(Clone)]
+pub struct WithBreaker<R, B, P = ()> {
+    inner: R,
+    breaker: B,
+    pred: P,
+}
+
+impl<R, B> WithBreaker<R, B> {
+    pub(crate) fn new(req: R, breaker: B) -> Self {
+        WithBreaker {
+            inner: req,
+            breaker,
+            pred: (),
+        }
+    }
+}
+
+impl<R, B, P> WithBreaker<R, B, P> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(breaker: B);
+    unsafe_unpinned!(pred: P);
+
+    /// Sets the predicate to decide which errors are counted as failures.
+    ///
+    /// By default, all errors are counted.
+    pub fn with_predicate<Q>(self, pred: Q) -> WithBreaker<R, B, Q>
+    where
+        R: BaseRequest,
+        Q: BreakerPredicate<R::Error>,
+    {
+        WithBreaker {
+            inner: self.inner,
+            breaker: self.breaker,
+            pred,
+        }
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, B, P> BaseRequest for WithBreaker<R, B, P>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = BreakerError<R::Error>;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, B, K, P, C> OneshotRequest<C> for WithBreaker<R, B, P>
+where
+    R: OneshotRequest<C>,
+    B: Deref<Target = CircuitBreaker<K>>,
+    K: Clock,
+    P: BreakerPredicate<R::Error>,
+{
+    type Response = WithBreakerResponse<R::Response, B, P>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let inner = if self.breaker.try_acquire() {
+            Some(self.inner.send_once(client))
+        } else {
+            None
+        };
+        WithBreakerResponse::new(inner, self.breaker, self.pred)
+    }
+}
+
+impl<R, B, K, P, C> Request<C> for WithBreaker<R, B, P>
+where
+    R: Request<C>,
+    B: Deref<Target = CircuitBreaker<K>> + Clone,
+    K: Clock,
+    P: BreakerPredicate<R::Error> + Clone,
+{
+    type Response = WithBreakerResponse<R::Response, B, P>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let breaker = self.as_mut().breaker().clone();
+        let pred = self.as_mut().pred().clone();
+        let inner = if breaker.try_acquire() {
+            Some(self.inner().send(client))
+        } else {
+            None
+        };
+        WithBreakerResponse::new(inner, breaker, pred)
+    }
+}
+
+impl<R, B, P> Unpin for WithBreaker<R, B, P> where R: Unpin {}
+
+/// Response for [`with_breaker`](crate::util::RequestExt::with_breaker)
+/// combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct WithBreakerResponse<F, B, P> {
+    inner: Option<F>,
+    breaker: B,
+    pred: P,
+}
+
+impl<F, B, P> WithBreakerResponse<F, B, P> {
+    unsafe_pinned!(inner: Option<F>);
+
+    fn new(inner: Option<F>, breaker: B, pred: P) -> Self {
+        WithBreakerResponse {
+            inner,
+            breaker,
+            pred,
+        }
+    }
+}
+
+impl<F, B, P> Unpin for WithBreakerResponse<F, B, P> where F: Unpin {}
+
+impl<F, B, K, P> Future for WithBreakerResponse<F, B, P>
+where
+    F: Response,
+    B: Deref<Target = CircuitBreaker<K>>,
+    K: Clock,
+    P: BreakerPredicate<F::Error>,
+{
+    type Output = Result<F::Ok, BreakerError<F::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = match self.as_mut().inner().as_pin_mut() {
+            Some(inner) => match inner.try_poll(ctx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => result,
+            },
+            None => return Poll::Ready(Err(BreakerError::open())),
+        };
+        let failed = match &result {
+            Ok(_) => false,
+            Err(e) => self.pred.is_failure(e),
+        };
+        self.breaker.record(failed);
+        Poll::Ready(result.map_err(BreakerError::from_err))
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::{BreakerErrorKind, WithBreaker};
+    use crate::retry::RetriableRequest;
+
+    impl<R, B, P> RetriableRequest for WithBreaker<R, B, P>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            match &error.inner {
+                BreakerErrorKind::Inner(e) => self.inner.should_retry(e, next_interval),
+                BreakerErrorKind::Open => false,
+            }
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::WithBreaker;
+    use crate::paginator::PagedRequest;
+
+    impl<R, B, P> PagedRequest for WithBreaker<R, B, P>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_hedge {
+    use super::WithBreaker;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, B, P> IdempotentRequest for WithBreaker<R, B, P> where R: IdempotentRequest {}
+}
@@ -0,0 +1,713 @@
+//! Caching responses of requests, which are stable for a while.
+use core::fmt::Debug;
+use core::future::Future;
+use core::hash::Hash;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use futures::future::{BoxFuture, FutureExt};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::breaker::{Clock, SystemClock};
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+type RequestResult<R> = Result<<R as BaseRequest>::Ok, <R as BaseRequest>::Error>;
+// the duration to cache errors, and how to copy them
+type NegativeCache<E> = Option<(Duration, fn(&E) -> E)>;
+
+/// A request which can be identified by a key, to cache its responses.
+///
+/// Requests with the same key are expected to have the same response.
+pub trait RequestKey: BaseRequest {
+    type Key;
+
+    fn key(&self) -> Self::Key;
+}
+
+impl<R> RequestKey for &R
+where
+    R: RequestKey,
+{
+    type Key = R::Key;
+
+    fn key(&self) -> Self::Key {
+        (**self).key()
+    }
+}
+
+impl<P> RequestKey for Pin<P>
+where
+    P: Deref,
+    <P as Deref>::Target: RequestKey,
+{
+    type Key = <<P as Deref>::Target as RequestKey>::Key;
+
+    fn key(&self) -> Self::Key {
+        (**self).key()
+    }
+}
+
+impl<R> RequestKey for Box<R>
+where
+    R: RequestKey,
+{
+    type Key = R::Key;
+
+    fn key(&self) -> Self::Key {
+        (**self).key()
+    }
+}
+
+impl<R> RequestKey for Rc<R>
+where
+    R: RequestKey,
+{
+    type Key = R::Key;
+
+    fn key(&self) -> Self::Key {
+        (**self).key()
+    }
+}
+
+impl<R> RequestKey for Arc<R>
+where
+    R: RequestKey,
+{
+    type Key = R::Key;
+
+    fn key(&self) -> Self::Key {
+        (**self).key()
+    }
+}
+
+/// Formats the `Debug` representation of a request into its key, for
+/// requests which can't be hashed, like the ones of Rusoto.
+///
+/// The key is formatted into a new `String` whenever a cache is looked up,
+/// and it may differ between versions of the crate defining the request, so
+/// it should not be kept in caches outliving the process.
+pub fn debug_key<T>(req: &T) -> String
+where
+    T: Debug + ?Sized,
+{
+    format!("{:?}", req)
+}
+
+/// A cached value with its lifetime.
+#[derive(Clone, Debug)]
+pub struct CacheEntry<V> {
+    value: V,
+    stale_at: Instant,
+    expires_at: Instant,
+}
+
+impl<V> CacheEntry<V> {
+    fn new(value: V, now: Instant, ttl: Duration, stale: Duration) -> Self {
+        let stale_at = now + ttl;
+        CacheEntry {
+            value,
+            stale_at,
+            expires_at: stale_at + stale,
+        }
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns the time since when the entry should be revalidated.
+    pub fn stale_at(&self) -> Instant {
+        self.stale_at
+    }
+
+    /// Returns the time since when the entry should not be used anymore.
+    pub fn expires_at(&self) -> Instant {
+        self.expires_at
+    }
+}
+
+/// A storage of cached responses.
+///
+/// Stores are shared by requests, so they are usually used by reference or
+/// with [`Arc`].
+pub trait CacheStore<K, V> {
+    /// Calls the given closure with the entry for the key, unless it is
+    /// absent or has expired.
+    fn with_entry<F, U>(&self, key: &K, f: F) -> Option<U>
+    where
+        F: FnOnce(&CacheEntry<V>) -> U;
+
+    /// Stores the entry for the key, replacing the previous one.
+    fn insert(&self, key: K, entry: CacheEntry<V>);
+
+    /// Returns the current time, by which entries of this store become stale
+    /// and expire.
+    fn now(&self) -> Instant {
+        SystemClock.now()
+    }
+}
+
+impl<S, K, V> CacheStore<K, V> for &S
+where
+    S: CacheStore<K, V> + ?Sized,
+{
+    fn with_entry<F, U>(&self, key: &K, f: F) -> Option<U>
+    where
+        F: FnOnce(&CacheEntry<V>) -> U,
+    {
+        (**self).with_entry(key, f)
+    }
+
+    fn insert(&self, key: K, entry: CacheEntry<V>) {
+        (**self).insert(key, entry)
+    }
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+impl<S, K, V> CacheStore<K, V> for Arc<S>
+where
+    S: CacheStore<K, V> + ?Sized,
+{
+    fn with_entry<F, U>(&self, key: &K, f: F) -> Option<U>
+    where
+        F: FnOnce(&CacheEntry<V>) -> U,
+    {
+        (**self).with_entry(key, f)
+    }
+
+    fn insert(&self, key: K, entry: CacheEntry<V>) {
+        (**self).insert(key, entry)
+    }
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// An in-memory [`CacheStore`], which evicts the least recently used entry
+/// when it is full.
+///
+/// Entries expire by a [`Clock`], which reads the system time by default.
+#[derive(Debug)]
+pub struct LruCache<K, V, T = SystemClock> {
+    capacity: usize,
+    clock: T,
+    inner: Mutex<LruInner<K, V>>,
+}
+
+#[derive(Debug)]
+struct LruInner<K, V> {
+    entries: HashMap<K, (CacheEntry<V>, u64)>,
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        LruCache::with_clock(capacity, SystemClock)
+    }
+}
+
+impl<K, V, T> LruCache<K, V, T>
+where
+    K: Hash + Eq + Clone,
+    T: Clock,
+{
+    /// Creates a cache whose entries expire by the given clock.
+    pub fn with_clock(capacity: usize, clock: T) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        LruCache {
+            capacity,
+            clock,
+            inner: Mutex::new(LruInner {
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes the entry for the key, to send the request again next time.
+    pub fn remove(&self, key: &K) {
+        let mut inner = self.lock();
+        if let Some((_, tick)) = inner.entries.remove(key) {
+            inner.recency.remove(&tick);
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.recency.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruInner<K, V>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<K, V, T> CacheStore<K, V> for LruCache<K, V, T>
+where
+    K: Hash + Eq + Clone,
+    T: Clock,
+{
+    fn with_entry<F, U>(&self, key: &K, f: F) -> Option<U>
+    where
+        F: FnOnce(&CacheEntry<V>) -> U,
+    {
+        let mut inner = self.lock();
+        let inner = &mut *inner;
+        let (entry, tick) = inner.entries.get_mut(key)?;
+        inner.recency.remove(tick);
+        if entry.expires_at <= self.now() {
+            inner.entries.remove(key);
+            return None;
+        }
+        inner.tick += 1;
+        *tick = inner.tick;
+        inner.recency.insert(inner.tick, key.clone());
+        Some(f(entry))
+    }
+
+    fn insert(&self, key: K, entry: CacheEntry<V>) {
+        let mut inner = self.lock();
+        let inner = &mut *inner;
+        inner.tick += 1;
+        inner.recency.insert(inner.tick, key.clone());
+        if let Some((_, tick)) = inner.entries.insert(key, (entry, inner.tick)) {
+            inner.recency.remove(&tick);
+        }
+        if inner.entries.len() > self.capacity {
+            let (&tick, _) = inner
+                .recency
+                .iter()
+                .next()
+                .expect("entries are tracked by their recency");
+            let key = inner
+                .recency
+                .remove(&tick)
+                .expect("entries are tracked by their recency");
+            inner.entries.remove(&key);
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.clock.now()
+    }
+}
+
+/// A way to drive responses revalidating stale entries, apart from the
+/// requests served the stale entries.
+///
+/// It is implemented for closures taking a boxed future, which can spawn it
+/// to any executor, like `|refresh| { tokio::spawn(refresh); }`.
+pub trait Revalidate<F> {
+    /// Drives the given response to completion, to store its result.
+    fn revalidate(&self, refresh: F);
+}
+
+/// Entries never become stale without
+/// [`with_stale_while_revalidate`](Cached::with_stale_while_revalidate), so
+/// nothing is revalidated.
+impl<F> Revalidate<F> for () {
+    fn revalidate(&self, _refresh: F) {}
+}
+
+impl<'a, F, G> Revalidate<F> for G
+where
+    F: Future + Send + 'a,
+    G: Fn(BoxFuture<'a, ()>),
+{
+    fn revalidate(&self, refresh: F) {
+        (self)(refresh.map(drop).boxed())
+    }
+}
+
+enum Lookup<V, K> {
+    Fresh(V),
+    Stale(V, K),
+    Miss(K),
+}
+
+/// Request for [`cached`](crate::util::RequestExt::cached) combinator.
+///
+/// Only successful responses are cached by default. Errors can be cached as
+/// well with [`with_negative_ttl`](Cached::with_negative_ttl).
+pub struct Cached<R, S, V = ()>
+where
+    R: BaseRequest,
+{
+    inner: R,
+    store: S,
+    ttl: Duration,
+    stale: Duration,
+    negative: NegativeCache<R::Error>,
+    revalidate: V,
+}
+
+impl<R, S> Cached<R, S>
+where
+    R: BaseRequest,
+{
+    pub(crate) fn new(req: R, store: S, ttl: Duration) -> Self {
+        Cached {
+            inner: req,
+            store,
+            ttl,
+            stale: Duration::default(),
+            negative: None,
+            revalidate: (),
+        }
+    }
+
+    /// Keeps the successful responses for the given duration after they have
+    /// become stale.
+    ///
+    /// A request met a stale entry is served the stale response as well as
+    /// the others, and sent to revalidate it in a response given to the
+    /// `revalidate` closure, which should drive the response, e.g. by
+    /// spawning it. Other requests met the entry are not sent, until the
+    /// entry expires.
+    pub fn with_stale_while_revalidate<'a, V>(
+        self,
+        stale: Duration,
+        revalidate: V,
+    ) -> Cached<R, S, V>
+    where
+        V: Fn(BoxFuture<'a, ()>),
+    {
+        Cached {
+            inner: self.inner,
+            store: self.store,
+            ttl: self.ttl,
+            stale,
+            negative: self.negative,
+            revalidate,
+        }
+    }
+}
+
+impl<R, S, V> Cached<R, S, V>
+where
+    R: BaseRequest,
+{
+    unsafe_pinned!(inner: R);
+
+    /// Caches errors too, for the given duration.
+    pub fn with_negative_ttl(self, ttl: Duration) -> Self
+    where
+        R::Error: Clone,
+    {
+        Cached {
+            negative: Some((ttl, Clone::clone)),
+            ..self
+        }
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, S, V> Cached<R, S, V>
+where
+    R: RequestKey,
+    R::Ok: Clone,
+    S: CacheStore<R::Key, RequestResult<R>>,
+{
+    /// Returns the cached result, and the key to store the result of a new
+    /// response if it is stale or absent.
+    fn lookup(&self) -> Lookup<RequestResult<R>, R::Key> {
+        let key = self.inner.key();
+        let negative = self.negative;
+        let now = self.store.now();
+        let found = self.store.with_entry(&key, |entry| {
+            let value = match &entry.value {
+                Ok(v) => Ok(v.clone()),
+                Err(e) => Err(negative.map(|(_, clone)| clone(e))?),
+            };
+            let stale = if entry.stale_at <= now {
+                Some(entry.expires_at)
+            } else {
+                None
+            };
+            Some((value, stale))
+        });
+        match found {
+            Some(Some((value, None))) => Lookup::Fresh(value),
+            Some(Some((Ok(v), Some(expires_at)))) => {
+                // serve the stale entry to others until revalidated.
+                let entry = CacheEntry {
+                    value: Ok(v.clone()),
+                    stale_at: expires_at,
+                    expires_at,
+                };
+                self.store.insert(self.inner.key(), entry);
+                Lookup::Stale(Ok(v), key)
+            }
+            // errors are never served stale
+            Some(Some((Err(_), Some(_)))) | Some(None) | None => Lookup::Miss(key),
+        }
+    }
+}
+
+impl<R, S, V> BaseRequest for Cached<R, S, V>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, S, V, C> OneshotRequest<C> for Cached<R, S, V>
+where
+    R: OneshotRequest<C> + RequestKey,
+    R::Ok: Clone,
+    S: CacheStore<R::Key, RequestResult<R>>,
+    V: Revalidate<CachedResponse<R::Response, S, R::Key>>,
+{
+    type Response = CachedResponse<R::Response, S, R::Key>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let options = (self.ttl, self.stale, self.negative);
+        match self.lookup() {
+            Lookup::Fresh(hit) => CachedResponse::from_hit(hit),
+            Lookup::Stale(hit, key) => {
+                let inner = self.inner.send_once(client);
+                let refresh = CachedResponse::from_miss(inner, self.store, key, options);
+                self.revalidate.revalidate(refresh);
+                CachedResponse::from_hit(hit)
+            }
+            Lookup::Miss(key) => {
+                let inner = self.inner.send_once(client);
+                CachedResponse::from_miss(inner, self.store, key, options)
+            }
+        }
+    }
+}
+
+impl<R, S, V, C> Request<C> for Cached<R, S, V>
+where
+    R: Request<C> + RequestKey,
+    R::Ok: Clone,
+    S: CacheStore<R::Key, RequestResult<R>> + Clone,
+    V: Revalidate<CachedResponse<R::Response, S, R::Key>>,
+{
+    type Response = CachedResponse<R::Response, S, R::Key>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let options = (self.ttl, self.stale, self.negative);
+        match self.lookup() {
+            Lookup::Fresh(hit) => CachedResponse::from_hit(hit),
+            Lookup::Stale(hit, key) => {
+                let store = self.store.clone();
+                let inner = self.as_mut().inner().send(client);
+                let refresh = CachedResponse::from_miss(inner, store, key, options);
+                self.revalidate.revalidate(refresh);
+                CachedResponse::from_hit(hit)
+            }
+            Lookup::Miss(key) => {
+                let store = self.store.clone();
+                let inner = self.inner().send(client);
+                CachedResponse::from_miss(inner, store, key, options)
+            }
+        }
+    }
+}
+
+impl<R, S, V> Clone for Cached<R, S, V>
+where
+    R: BaseRequest + Clone,
+    S: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        Cached {
+            inner: self.inner.clone(),
+            store: self.store.clone(),
+            ttl: self.ttl,
+            stale: self.stale,
+            negative: self.negative,
+            revalidate: self.revalidate.clone(),
+        }
+    }
+}
+
+impl<R, S, V> Unpin for Cached<R, S, V> where R: BaseRequest + Unpin {}
+
+type CacheOptions<E> = (Duration, Duration, NegativeCache<E>);
+
+/// Response for [`cached`](crate::util::RequestExt::cached) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct CachedResponse<F, S, K>
+where
+    F: Response,
+{
+    hit: Option<Result<F::Ok, F::Error>>,
+    inner: Option<F>,
+    store: Option<S>,
+    key: Option<K>,
+    options: CacheOptions<F::Error>,
+}
+
+impl<F, S, K> CachedResponse<F, S, K>
+where
+    F: Response,
+{
+    unsafe_unpinned!(hit: Option<Result<F::Ok, F::Error>>);
+    unsafe_pinned!(inner: Option<F>);
+    unsafe_unpinned!(store: Option<S>);
+    unsafe_unpinned!(key: Option<K>);
+
+    fn from_hit(result: Result<F::Ok, F::Error>) -> Self {
+        CachedResponse {
+            hit: Some(result),
+            inner: None,
+            store: None,
+            key: None,
+            options: (Duration::default(), Duration::default(), None),
+        }
+    }
+
+    fn from_miss(inner: F, store: S, key: K, options: CacheOptions<F::Error>) -> Self {
+        CachedResponse {
+            hit: None,
+            inner: Some(inner),
+            store: Some(store),
+            key: Some(key),
+            options,
+        }
+    }
+}
+
+impl<F, S, K> Unpin for CachedResponse<F, S, K> where F: Response + Unpin {}
+
+impl<F, S, K> Future for CachedResponse<F, S, K>
+where
+    F: Response,
+    F::Ok: Clone,
+    S: CacheStore<K, Result<F::Ok, F::Error>>,
+{
+    type Output = Result<F::Ok, F::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(hit) = self.as_mut().hit().take() {
+            return Poll::Ready(hit);
+        }
+
+        let result = match self.as_mut().inner().as_pin_mut() {
+            Some(inner) => match inner.try_poll(ctx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => result,
+            },
+            None => panic!("CachedResponse polled after completion"),
+        };
+        self.as_mut().inner().set(None);
+
+        let store = self
+            .as_mut()
+            .store()
+            .take()
+            .expect("CachedResponse polled after completion");
+        let key = self
+            .as_mut()
+            .key()
+            .take()
+            .expect("CachedResponse polled after completion");
+        let now = store.now();
+        let (ttl, stale, negative) = self.options;
+        let entry = match (&result, negative) {
+            (Ok(v), _) => Some(CacheEntry::new(Ok(v.clone()), now, ttl, stale)),
+            (Err(e), Some((ttl, clone))) => Some(CacheEntry::new(
+                Err(clone(e)),
+                now,
+                ttl,
+                Duration::default(),
+            )),
+            (Err(_), None) => None,
+        };
+        if let Some(entry) = entry {
+            store.insert(key, entry);
+        }
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::Cached;
+    use crate::request::BaseRequest;
+    use crate::retry::RetriableRequest;
+
+    impl<R, S, V> RetriableRequest for Cached<R, S, V>
+    where
+        R: BaseRequest + RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::Cached;
+    use crate::paginator::PagedRequest;
+
+    impl<R, S, V> PagedRequest for Cached<R, S, V>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_hedge {
+    use super::Cached;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, S, V> IdempotentRequest for Cached<R, S, V> where R: IdempotentRequest {}
+}
+
+impl<R, S, V> RequestKey for Cached<R, S, V>
+where
+    R: RequestKey,
+{
+    type Key = R::Key;
+
+    fn key(&self) -> Self::Key {
+        self.inner.key()
+    }
+}
@@ -0,0 +1,191 @@
+//! Cooperative cancellation of retrials and paginators in flight.
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[cfg(feature = "backoff")]
+pub use self::impl_retry::{CancellableDelay, CancellableTimer};
+
+/// A token to signal cancellation to every [`Retrying`] and [`Paginator`]
+/// holding a clone of it.
+///
+/// Cancelling a token aborts the pending backoff waits of retrials
+/// immediately with [`RetryError::is_cancelled`], and makes paginators end
+/// after the page in flight.
+///
+/// [`Retrying`]: crate::retry::Retrying
+/// [`Paginator`]: crate::paginator::Paginator
+/// [`RetryError::is_cancelled`]: crate::retry::RetryError::is_cancelled
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    waiters: Mutex<Waiters>,
+}
+
+#[derive(Default)]
+struct Waiters {
+    next_id: u64,
+    wakers: HashMap<u64, Waker>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Signals cancellation, and wakes up all tasks waiting for it.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let wakers: Vec<_> = self.lock().wakers.drain().map(|(_, w)| w).collect();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns a future which resolves when this token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+            slot: None,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Waiters> {
+        self.inner.waiters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Future for [`CancellationToken::cancelled`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Cancelled {
+    token: CancellationToken,
+    slot: Option<u64>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+
+        let slot = self.slot;
+        let id = {
+            let mut waiters = self.token.lock();
+            let id = slot.unwrap_or_else(|| {
+                let id = waiters.next_id;
+                waiters.next_id += 1;
+                id
+            });
+            waiters.wakers.insert(id, ctx.waker().clone());
+            id
+        };
+        self.slot = Some(id);
+
+        // It may have been cancelled while registering the waker.
+        if self.token.is_cancelled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        if let Some(id) = self.slot {
+            self.token.lock().wakers.remove(&id);
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use core::time::Duration;
+
+    use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+    use super::{CancellationToken, Cancelled};
+    use crate::response::Response;
+    use crate::retry::{RetryError, Timer};
+
+    /// A timer whose delays fail with [`RetryError::is_cancelled`] as soon as
+    /// the token is cancelled.
+    #[derive(Clone)]
+    pub struct CancellableTimer<T> {
+        inner: T,
+        token: CancellationToken,
+    }
+
+    impl<T> CancellableTimer<T> {
+        pub fn new(inner: T, token: CancellationToken) -> Self {
+            CancellableTimer { inner, token }
+        }
+
+        pub fn token(&self) -> &CancellationToken {
+            &self.token
+        }
+
+        pub fn into_inner(self) -> T {
+            self.inner
+        }
+    }
+
+    impl<T> Timer for CancellableTimer<T>
+    where
+        T: Timer,
+    {
+        type Delay = CancellableDelay<T::Delay>;
+
+        fn expires_in(&mut self, interval: Duration) -> Self::Delay {
+            CancellableDelay {
+                inner: self.inner.expires_in(interval),
+                cancelled: self.token.cancelled(),
+            }
+        }
+    }
+
+    /// A delayed response from [`CancellableTimer`].
+    #[must_use = "responses do nothing unless polled"]
+    pub struct CancellableDelay<D> {
+        inner: D,
+        cancelled: Cancelled,
+    }
+
+    impl<D> CancellableDelay<D> {
+        unsafe_pinned!(inner: D);
+        unsafe_unpinned!(cancelled: Cancelled);
+    }
+
+    impl<D> Unpin for CancellableDelay<D> where D: Unpin {}
+
+    impl<D> Future for CancellableDelay<D>
+    where
+        D: Response<Ok = (), Error = RetryError>,
+    {
+        type Output = Result<(), RetryError>;
+
+        fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+            if let Poll::Ready(()) = Pin::new(self.as_mut().cancelled()).poll(ctx) {
+                return Poll::Ready(Err(RetryError::cancelled()));
+            }
+            self.inner().try_poll(ctx)
+        }
+    }
+}
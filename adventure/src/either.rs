@@ -0,0 +1,100 @@
+//! Request implementations for [`Either`], to branch without boxing.
+use core::pin::Pin;
+
+use futures::future::{IntoFuture, TryFutureExt};
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::paginator::PagedRequest;
+use crate::request::{BaseRequest, Request};
+
+pub use futures::future::Either;
+
+/// Either of two types of requests, which have the same types of the
+/// successful value and the error.
+///
+/// It forwards [`PagedRequest`] and `RetriableRequest` to the active branch.
+impl<A, B> BaseRequest for Either<A, B>
+where
+    A: BaseRequest,
+    B: BaseRequest<Ok = A::Ok, Error = A::Error>,
+{
+    type Ok = A::Ok;
+    type Error = A::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        match self {
+            Either::Left(a) => a.extensions(),
+            Either::Right(b) => b.extensions(),
+        }
+    }
+}
+
+impl<A, B, C> OneshotRequest<C> for Either<A, B>
+where
+    A: OneshotRequest<C>,
+    B: OneshotRequest<C, Ok = A::Ok, Error = A::Error>,
+{
+    type Response = Either<IntoFuture<A::Response>, IntoFuture<B::Response>>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        match self {
+            Either::Left(a) => Either::Left(a.send_once(client).into_future()),
+            Either::Right(b) => Either::Right(b.send_once(client).into_future()),
+        }
+    }
+}
+
+impl<A, B, C> Request<C> for Either<A, B>
+where
+    A: Request<C>,
+    B: Request<C, Ok = A::Ok, Error = A::Error>,
+{
+    type Response = Either<IntoFuture<A::Response>, IntoFuture<B::Response>>;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        // both branches are structurally pinned.
+        unsafe {
+            match self.get_unchecked_mut() {
+                Either::Left(a) => Either::Left(Pin::new_unchecked(a).send(client).into_future()),
+                Either::Right(b) => Either::Right(Pin::new_unchecked(b).send(client).into_future()),
+            }
+        }
+    }
+}
+
+impl<A, B> PagedRequest for Either<A, B>
+where
+    A: PagedRequest,
+    B: PagedRequest<Ok = A::Ok, Error = A::Error>,
+{
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        match self {
+            Either::Left(a) => a.advance(response),
+            Either::Right(b) => b.advance(response),
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::Either;
+    use crate::retry::RetriableRequest;
+
+    impl<A, B> RetriableRequest for Either<A, B>
+    where
+        A: RetriableRequest,
+        B: RetriableRequest<Ok = A::Ok, Error = A::Error>,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            match self {
+                Either::Left(a) => a.should_retry(error, next_interval),
+                Either::Right(b) => b.should_retry(error, next_interval),
+            }
+        }
+    }
+}
@@ -0,0 +1,510 @@
+//! Sending a stream of requests as a bulk job, with bounded concurrency.
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::sync::Arc;
+
+use futures::stream::{FuturesUnordered, Stream};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::rate_limit::{RateLimited, RateLimitedResponse, RateLimiter, TokenBucket};
+use crate::request::{BaseRequest, Request};
+use crate::retry::{
+    Backoff, ExponentialBackoff, Retrial, RetrialPredicate, RetryError, Retrying, Timer,
+};
+
+type Job<R, C, T, B, F, L> = Retrial<Retrying<Attempt<R, L, T>, T, B, Counted<F>>, C>;
+type Jobs<R, C, T, B, F, L> = FuturesUnordered<Indexed<Job<R, C, T, B, F, L>>>;
+type JobResult<R> = Result<<R as BaseRequest>::Ok, RetryError<<R as BaseRequest>::Error>>;
+type RequestReport<R> = Report<<R as BaseRequest>::Ok, <R as BaseRequest>::Error>;
+type Successes<T> = Vec<(usize, T)>;
+type Failures<E> = Vec<(usize, RetryError<E>)>;
+
+/// A bulk job to send every request from a stream to the same client, like
+/// tagging all of the resources in an account.
+///
+/// Each request is retried on failure with the backoff strategy and the
+/// predicate, and the requests which have failed in the end are collected in
+/// the [`Report`], instead of stopping the job.
+///
+/// By default, at most 16 requests are in flight at the same time, and their
+/// failures are retried if the requests implement [`RetriableRequest`] and
+/// decide to do so.
+///
+/// [`RetriableRequest`]: crate::retry::RetriableRequest
+pub struct Executor<C, T, B = ExponentialBackoff, F = (), L = TokenBucket, P = fn(&Progress)> {
+    client: C,
+    timer: T,
+    backoff: B,
+    pred: F,
+    concurrency: usize,
+    limiter: Option<Arc<L>>,
+    progress: Option<P>,
+}
+
+impl<C, T> Executor<C, T> {
+    /// Creates an executor sending requests to the given client, using the
+    /// given timer to wait between attempts.
+    pub fn new(client: C, timer: T) -> Self {
+        Executor {
+            client,
+            timer,
+            backoff: Default::default(),
+            pred: (),
+            concurrency: 16,
+            limiter: None,
+            progress: None,
+        }
+    }
+}
+
+impl<C, T, B, F, L, P> Executor<C, T, B, F, L, P> {
+    /// Sets the backoff strategy between attempts of each request.
+    pub fn with_backoff<B2>(self, backoff: B2) -> Executor<C, T, B2, F, L, P>
+    where
+        B2: Backoff,
+    {
+        Executor {
+            client: self.client,
+            timer: self.timer,
+            backoff,
+            pred: self.pred,
+            concurrency: self.concurrency,
+            limiter: self.limiter,
+            progress: self.progress,
+        }
+    }
+
+    /// Sets the predicate to decide which failures are retried, instead of
+    /// [`RetriableRequest::should_retry`].
+    ///
+    /// [`RetriableRequest::should_retry`]: crate::retry::RetriableRequest::should_retry
+    pub fn with_predicate<F2>(self, pred: F2) -> Executor<C, T, B, F2, L, P> {
+        Executor {
+            client: self.client,
+            timer: self.timer,
+            backoff: self.backoff,
+            pred,
+            concurrency: self.concurrency,
+            limiter: self.limiter,
+            progress: self.progress,
+        }
+    }
+
+    /// Sets the maximum number of requests in flight at the same time.
+    pub fn with_concurrency(self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "concurrency must be positive");
+        Executor {
+            concurrency,
+            ..self
+        }
+    }
+
+    /// Waits for a permit from the given limiter before sending each
+    /// attempt, including retrials.
+    pub fn with_rate_limit<L2>(self, limiter: L2) -> Executor<C, T, B, F, L2, P>
+    where
+        L2: RateLimiter,
+    {
+        Executor {
+            client: self.client,
+            timer: self.timer,
+            backoff: self.backoff,
+            pred: self.pred,
+            concurrency: self.concurrency,
+            limiter: Some(Arc::new(limiter)),
+            progress: self.progress,
+        }
+    }
+
+    /// Calls the given function whenever a request has completed, whether
+    /// it has succeeded or not.
+    pub fn with_progress<P2>(self, progress: P2) -> Executor<C, T, B, F, L, P2>
+    where
+        P2: FnMut(&Progress),
+    {
+        Executor {
+            client: self.client,
+            timer: self.timer,
+            backoff: self.backoff,
+            pred: self.pred,
+            concurrency: self.concurrency,
+            limiter: self.limiter,
+            progress: Some(progress),
+        }
+    }
+
+    /// Returns a future which sends all of the requests from the given
+    /// stream, and results in the report of them.
+    pub fn execute<S>(self, requests: S) -> Execute<S, C, T, B, F, L, P>
+    where
+        S: Stream,
+        S::Item: OneshotRequest<C> + Clone,
+        T: Timer + Clone,
+        B: Backoff,
+        F: RetrialPredicate<S::Item>,
+        L: RateLimiter,
+    {
+        Execute {
+            requests: Some(requests),
+            client: self.client,
+            timer: self.timer,
+            backoff: self.backoff,
+            pred: Arc::new(self.pred),
+            concurrency: self.concurrency,
+            limiter: Limit(self.limiter),
+            on_progress: self.progress,
+            retries: Arc::new(AtomicUsize::new(0)),
+            state: Default::default(),
+            in_flight: FuturesUnordered::new(),
+            report: Some(Report {
+                successes: Vec::new(),
+                failures: Vec::new(),
+                retries: 0,
+            }),
+        }
+    }
+}
+
+/// A snapshot of the progress of an [`Executor`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    started: usize,
+    succeeded: usize,
+    failed: usize,
+    retries: usize,
+}
+
+impl Progress {
+    /// Returns the number of requests taken from the stream so far.
+    pub fn started(&self) -> usize {
+        self.started
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.succeeded
+    }
+
+    /// Returns the number of requests which have failed after all of their
+    /// attempts.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Returns the number of requests which are sent but not completed yet.
+    pub fn in_flight(&self) -> usize {
+        self.started - self.succeeded - self.failed
+    }
+
+    /// Returns the number of retrials of all requests so far.
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+}
+
+/// The result of an [`Executor`], with the requests indexed by their order
+/// in the stream.
+#[derive(Debug)]
+pub struct Report<T, E> {
+    successes: Successes<T>,
+    failures: Failures<E>,
+    retries: usize,
+}
+
+impl<T, E> Report<T, E> {
+    /// Returns the successful values of requests, in the order of their
+    /// completion.
+    pub fn successes(&self) -> &[(usize, T)] {
+        &self.successes
+    }
+
+    /// Returns the errors of requests which have failed after all of their
+    /// attempts, in the order of their completion.
+    pub fn failures(&self) -> &[(usize, RetryError<E>)] {
+        &self.failures
+    }
+
+    /// Returns the number of retrials of all requests.
+    pub fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// Consumes this report, returning the successes and the failures.
+    pub fn into_parts(self) -> (Successes<T>, Failures<E>) {
+        (self.successes, self.failures)
+    }
+}
+
+/// Future for [`Executor::execute`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Execute<S, C, T, B, F, L, P>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C> + Clone,
+    T: Timer + Clone,
+    B: Backoff,
+    F: RetrialPredicate<S::Item>,
+    L: RateLimiter,
+{
+    requests: Option<S>,
+    client: C,
+    timer: T,
+    backoff: B,
+    pred: Arc<F>,
+    concurrency: usize,
+    limiter: Limit<L>,
+    on_progress: Option<P>,
+    retries: Arc<AtomicUsize>,
+    state: Progress,
+    in_flight: Jobs<S::Item, C, T, B, F, L>,
+    report: Option<RequestReport<S::Item>>,
+}
+
+impl<S, C, T, B, F, L, P> Execute<S, C, T, B, F, L, P>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C> + Clone,
+    T: Timer + Clone,
+    B: Backoff,
+    F: RetrialPredicate<S::Item>,
+    L: RateLimiter,
+{
+    unsafe_pinned!(requests: Option<S>);
+    unsafe_unpinned!(state: Progress);
+    unsafe_unpinned!(in_flight: Jobs<S::Item, C, T, B, F, L>);
+    unsafe_unpinned!(on_progress: Option<P>);
+    unsafe_unpinned!(
+        report: Option<RequestReport<S::Item>>
+    );
+
+    /// Returns the progress of the job so far.
+    pub fn progress(&self) -> Progress {
+        Progress {
+            retries: self.retries.load(Ordering::SeqCst),
+            ..self.state
+        }
+    }
+}
+
+impl<S, C, T, B, F, L, P> Unpin for Execute<S, C, T, B, F, L, P>
+where
+    S: Stream + Unpin,
+    S::Item: OneshotRequest<C> + Clone,
+    T: Timer + Clone,
+    B: Backoff,
+    F: RetrialPredicate<S::Item>,
+    L: RateLimiter,
+{
+}
+
+impl<S, C, T, B, F, L, P> Future for Execute<S, C, T, B, F, L, P>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C> + Clone + Unpin,
+    C: Clone,
+    T: Timer + Clone + Unpin,
+    B: Backoff + Clone + Unpin,
+    F: RetrialPredicate<S::Item>,
+    L: RateLimiter,
+    P: FnMut(&Progress),
+{
+    type Output = RequestReport<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            while self.in_flight.len() < self.concurrency {
+                let requests = match self.as_mut().requests().as_pin_mut() {
+                    Some(requests) => requests,
+                    None => break,
+                };
+                match requests.poll_next(ctx) {
+                    Poll::Ready(Some(request)) => self.as_mut().start(request),
+                    Poll::Ready(None) => self.as_mut().requests().set(None),
+                    Poll::Pending => break,
+                }
+            }
+
+            match Pin::new(self.as_mut().in_flight()).poll_next(ctx) {
+                Poll::Ready(Some((index, result))) => self.as_mut().finish(index, result),
+                Poll::Ready(None) if self.requests.is_none() => {
+                    let mut report = self
+                        .as_mut()
+                        .report()
+                        .take()
+                        .expect("Execute polled after completion");
+                    report.retries = self.retries.load(Ordering::SeqCst);
+                    return Poll::Ready(report);
+                }
+                _ => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<S, C, T, B, F, L, P> Execute<S, C, T, B, F, L, P>
+where
+    S: Stream,
+    S::Item: OneshotRequest<C> + Clone + Unpin,
+    C: Clone,
+    T: Timer + Clone + Unpin,
+    B: Backoff + Clone + Unpin,
+    F: RetrialPredicate<S::Item>,
+    L: RateLimiter,
+    P: FnMut(&Progress),
+{
+    fn start(mut self: Pin<&mut Self>, request: S::Item) {
+        let attempt = Attempt {
+            request,
+            limiter: self.limiter.clone(),
+            timer: self.timer.clone(),
+        };
+        let pred = Counted {
+            pred: self.pred.clone(),
+            retries: self.retries.clone(),
+        };
+        // the elapsed time of the backoff counts from the start of each
+        // request, not from the start of the job.
+        let mut backoff = self.backoff.clone();
+        backoff.reset();
+        let retrial = Retrying::new(attempt, self.timer.clone(), backoff)
+            .with_predicate(pred)
+            .send_once(self.client.clone());
+
+        let index = self.state.started;
+        self.as_mut().state().started += 1;
+        self.as_mut().in_flight().push(Indexed {
+            index,
+            inner: retrial,
+        });
+    }
+
+    fn finish(mut self: Pin<&mut Self>, index: usize, result: JobResult<S::Item>) {
+        let report = self
+            .as_mut()
+            .report()
+            .as_mut()
+            .expect("Execute polled after completion");
+        match result {
+            Ok(ok) => {
+                report.successes.push((index, ok));
+                self.as_mut().state().succeeded += 1;
+            }
+            Err(e) => {
+                report.failures.push((index, e));
+                self.as_mut().state().failed += 1;
+            }
+        }
+
+        let progress = self.progress();
+        if let Some(f) = self.as_mut().on_progress().as_mut() {
+            f(&progress);
+        }
+    }
+}
+
+// a request to send a clone of the request for each attempt, after taking a
+// permit from the limiter.
+struct Attempt<R, L, T> {
+    request: R,
+    limiter: Limit<L>,
+    timer: T,
+}
+
+impl<R, L, T> BaseRequest for Attempt<R, L, T>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.request.extensions()
+    }
+}
+
+impl<R, L, T, C> Request<C> for Attempt<R, L, T>
+where
+    R: OneshotRequest<C> + Clone,
+    L: RateLimiter,
+    T: Timer + Clone,
+{
+    type Response = RateLimitedResponse<R, T::Delay, C>;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        let limiter = self.limiter.clone();
+        RateLimited::new(self.request.clone(), limiter, self.timer.clone()).send_once(client)
+    }
+}
+
+struct Limit<L>(Option<Arc<L>>);
+
+impl<L> Clone for Limit<L> {
+    fn clone(&self) -> Self {
+        Limit(self.0.clone())
+    }
+}
+
+impl<L> RateLimiter for Limit<L>
+where
+    L: RateLimiter,
+{
+    fn acquire(&self) -> Duration {
+        match &self.0 {
+            Some(limiter) => limiter.acquire(),
+            None => Duration::default(),
+        }
+    }
+}
+
+// a predicate counting the retrials it has allowed.
+struct Counted<F> {
+    pred: Arc<F>,
+    retries: Arc<AtomicUsize>,
+}
+
+impl<R, L, T, F> RetrialPredicate<Attempt<R, L, T>> for Counted<F>
+where
+    R: BaseRequest,
+    F: RetrialPredicate<R>,
+{
+    fn should_retry(
+        &self,
+        req: &Attempt<R, L, T>,
+        err: &R::Error,
+        next_interval: Duration,
+    ) -> bool {
+        let retry = self.pred.should_retry(&req.request, err, next_interval);
+        if retry {
+            self.retries.fetch_add(1, Ordering::SeqCst);
+        }
+        retry
+    }
+}
+
+struct Indexed<F> {
+    index: usize,
+    inner: F,
+}
+
+impl<F> Indexed<F> {
+    unsafe_pinned!(inner: F);
+}
+
+impl<F> Future for Indexed<F>
+where
+    F: Future,
+{
+    type Output = (usize, F::Output);
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let index = self.index;
+        self.as_mut()
+            .inner()
+            .poll(ctx)
+            .map(|output| (index, output))
+    }
+}
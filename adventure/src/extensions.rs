@@ -0,0 +1,205 @@
+//! Typed metadata attached to requests, like trace IDs or priorities.
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use core::any::{Any, TypeId};
+use core::fmt;
+use core::pin::Pin;
+
+use pin_utils::unsafe_pinned;
+
+use crate::oneshot::OneshotRequest;
+use crate::paginator::PagedRequest;
+use crate::request::{BaseRequest, Request};
+
+/// A map of values keyed by their types.
+///
+/// Values are shared between clones, so it is cheap to clone the extensions
+/// of a request.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: BTreeMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Inserts a value, returning `true` if it replaced the previous value of
+    /// the same type.
+    pub fn insert<T>(&mut self, value: T) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map
+            .insert(TypeId::of::<T>(), Arc::new(value))
+            .is_some()
+    }
+
+    /// Returns the value of the given type.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|v| (&**v as &dyn Any).downcast_ref())
+    }
+
+    /// Removes the value of the given type, returning `true` if it existed.
+    pub fn remove<T>(&mut self) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.map.remove(&TypeId::of::<T>()).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
+
+/// Request for [`with_extension`](crate::util::RequestExt::with_extension)
+/// combinator.
+///
+/// It starts with the extensions of the inner request, so the values
+/// attached to it are still visible.
+#[derive(Clone)]
+pub struct WithExtensions<R> {
+    inner: R,
+    extensions: Extensions,
+}
+
+impl<R> WithExtensions<R>
+where
+    R: BaseRequest,
+{
+    pub(crate) fn new(req: R) -> Self {
+        let extensions = req.extensions().cloned().unwrap_or_default();
+        WithExtensions {
+            inner: req,
+            extensions,
+        }
+    }
+}
+
+impl<R> WithExtensions<R> {
+    unsafe_pinned!(inner: R);
+
+    /// Attaches another value to this request.
+    pub fn with_extension<T>(mut self, value: T) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        self.extensions.insert(value);
+        self
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> BaseRequest for WithExtensions<R>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        Some(&self.extensions)
+    }
+}
+
+impl<R, C> OneshotRequest<C> for WithExtensions<R>
+where
+    R: OneshotRequest<C>,
+{
+    type Response = R::Response;
+
+    fn send_once(self, client: C) -> Self::Response {
+        self.inner.send_once(client)
+    }
+}
+
+impl<R, C> Request<C> for WithExtensions<R>
+where
+    R: Request<C>,
+{
+    type Response = R::Response;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        self.inner().send(client)
+    }
+}
+
+impl<R> PagedRequest for WithExtensions<R>
+where
+    R: PagedRequest,
+{
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.inner.advance(response)
+    }
+}
+
+impl<R> Unpin for WithExtensions<R> where R: Unpin {}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::WithExtensions;
+    use crate::retry::RetriableRequest;
+
+    impl<R> RetriableRequest for WithExtensions<R>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_hedge {
+    use super::WithExtensions;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R> IdempotentRequest for WithExtensions<R> where R: IdempotentRequest {}
+}
+
+#[cfg(feature = "std")]
+mod impl_cache {
+    use super::WithExtensions;
+    use crate::cache::RequestKey;
+
+    impl<R> RequestKey for WithExtensions<R>
+    where
+        R: RequestKey,
+    {
+        type Key = R::Key;
+
+        fn key(&self) -> Self::Key {
+            self.inner.key()
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Hedged requests, which send redundant copies to reduce the tail latency.
+use alloc::{boxed::Box, rc::Rc, sync::Arc};
+use core::future::Future;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+use futures::future::{IntoFuture, TryFutureExt};
+use futures::stream::{FuturesUnordered, StreamExt};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+use crate::retry::Timer;
+
+#[cfg(feature = "tokio-timer")]
+use crate::retry::TokioTimer;
+
+#[cfg(feature = "tokio-timer")]
+pub type HedgeTokio<R> = Hedge<R, TokioTimer>;
+
+/// A request which has no additional effect even if it is sent many times.
+///
+/// Only these requests are allowed to be [hedged](crate::util::RequestExt::hedge).
+pub trait IdempotentRequest: BaseRequest {}
+
+impl<R> IdempotentRequest for &R where R: IdempotentRequest {}
+
+impl<P> IdempotentRequest for Pin<P>
+where
+    P: Deref,
+    <P as Deref>::Target: IdempotentRequest,
+{
+}
+
+impl<R> IdempotentRequest for Box<R> where R: IdempotentRequest {}
+
+impl<R> IdempotentRequest for Rc<R> where R: IdempotentRequest {}
+
+impl<R> IdempotentRequest for Arc<R> where R: IdempotentRequest {}
+
+/// Request for [`hedge`](crate::util::RequestExt::hedge) combinator.
+///
+/// By default, at most one additional copy is sent. The first completed
+/// response is taken, whether it has succeeded or not, and the others are
+/// dropped.
+#[derive(Clone)]
+pub struct Hedge<R, T> {
+    inner: R,
+    timer: T,
+    delay: Duration,
+    max_hedges: usize,
+}
+
+impl<R, T> Hedge<R, T> {
+    pub(crate) fn new(req: R, timer: T, delay: Duration) -> Self {
+        Hedge {
+            inner: req,
+            timer,
+            delay,
+            max_hedges: 1,
+        }
+    }
+
+    /// Limits the number of additional copies to send for each response.
+    pub fn with_max_hedges(self, max_hedges: usize) -> Self {
+        Hedge { max_hedges, ..self }
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, T> BaseRequest for Hedge<R, T>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, T, C> OneshotRequest<C> for Hedge<R, T>
+where
+    R: Request<C> + IdempotentRequest,
+    T: Timer,
+    C: Clone,
+{
+    type Response = Hedged<R, T, C>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        Hedged::new(self, client)
+    }
+}
+
+impl<R, T, C> Request<C> for Hedge<R, T>
+where
+    R: Request<C> + IdempotentRequest + Clone,
+    T: Timer + Clone,
+    C: Clone,
+{
+    type Response = Hedged<R, T, C>;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        Hedged::new(self.clone(), client)
+    }
+}
+
+impl<R, T> IdempotentRequest for Hedge<R, T> where R: IdempotentRequest {}
+
+/// Response for [`hedge`](crate::util::RequestExt::hedge) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct Hedged<R, T, C>
+where
+    R: Request<C>,
+    T: Timer,
+{
+    request: Pin<Box<R>>,
+    timer: T,
+    client: C,
+    delay: Duration,
+    remaining: usize,
+    responses: FuturesUnordered<IntoFuture<R::Response>>,
+    wait: Option<T::Delay>,
+}
+
+impl<R, T, C> Hedged<R, T, C>
+where
+    R: Request<C>,
+    T: Timer,
+    C: Clone,
+{
+    unsafe_unpinned!(responses: FuturesUnordered<IntoFuture<R::Response>>);
+    unsafe_pinned!(wait: Option<T::Delay>);
+
+    fn new(hedge: Hedge<R, T>, client: C) -> Self {
+        let mut hedged = Hedged {
+            request: Box::pin(hedge.inner),
+            timer: hedge.timer,
+            client,
+            delay: hedge.delay,
+            remaining: hedge.max_hedges,
+            responses: FuturesUnordered::new(),
+            wait: None,
+        };
+        hedged.wait = hedged.send_copy();
+        hedged
+    }
+
+    fn hedge(mut self: Pin<&mut Self>) {
+        self.as_mut().wait().set(None);
+        // `wait` is the only field structurally pinned, and is not moved here.
+        let this = unsafe { self.as_mut().get_unchecked_mut() };
+        this.remaining -= 1;
+        let wait = this.send_copy();
+        self.wait().set(wait);
+    }
+
+    fn send_copy(&mut self) -> Option<T::Delay> {
+        let client = self.client.clone();
+        let response = self.request.as_mut().send(client);
+        self.responses.push(response.into_future());
+        if self.remaining > 0 {
+            Some(self.timer.expires_in(self.delay))
+        } else {
+            None
+        }
+    }
+}
+
+impl<R, T, C> Unpin for Hedged<R, T, C>
+where
+    R: Request<C>,
+    T: Timer,
+    T::Delay: Unpin,
+{
+}
+
+impl<R, T, C> Future for Hedged<R, T, C>
+where
+    R: Request<C>,
+    T: Timer,
+    C: Clone,
+{
+    type Output = Result<R::Ok, R::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            if let Poll::Ready(Some(result)) = self.as_mut().responses().poll_next_unpin(ctx) {
+                return Poll::Ready(result);
+            }
+
+            match self.as_mut().wait().as_pin_mut().map(|w| w.try_poll(ctx)) {
+                None | Some(Poll::Pending) => return Poll::Pending,
+                Some(Poll::Ready(Ok(()))) => self.as_mut().hedge(),
+                Some(Poll::Ready(Err(_))) => {
+                    // the timer has gone, just wait for copies already sent.
+                    self.as_mut().wait().set(None);
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::Hedge;
+    use crate::retry::RetriableRequest;
+
+    impl<R, T> RetriableRequest for Hedge<R, T>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::Hedge;
+    use crate::paginator::PagedRequest;
+
+    impl<R, T> PagedRequest for Hedge<R, T>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Composable layers and middlewares, to wrap requests with cross-cutting
+//! concerns.
+use core::pin::Pin;
+
+use pin_utils::pin_mut;
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// A decorator which wraps a request into another one.
+pub trait Layer<R> {
+    /// The type of wrapped requests.
+    type Request;
+
+    fn layer(&self, req: R) -> Self::Request;
+}
+
+impl<L, R> Layer<R> for &L
+where
+    L: Layer<R> + ?Sized,
+{
+    type Request = L::Request;
+
+    fn layer(&self, req: R) -> Self::Request {
+        (**self).layer(req)
+    }
+}
+
+/// A [`Layer`] which returns the request as is.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Identity;
+
+impl<R> Layer<R> for Identity {
+    type Request = R;
+
+    fn layer(&self, req: R) -> Self::Request {
+        req
+    }
+}
+
+/// Two layers, where `outer` wraps the request wrapped by `inner`.
+#[derive(Clone, Copy, Debug)]
+pub struct Stack<Inner, Outer> {
+    inner: Inner,
+    outer: Outer,
+}
+
+impl<Inner, Outer> Stack<Inner, Outer> {
+    pub fn new(inner: Inner, outer: Outer) -> Self {
+        Stack { inner, outer }
+    }
+}
+
+impl<R, Inner, Outer> Layer<R> for Stack<Inner, Outer>
+where
+    Inner: Layer<R>,
+    Outer: Layer<Inner::Request>,
+{
+    type Request = Outer::Request;
+
+    fn layer(&self, req: R) -> Self::Request {
+        self.outer.layer(self.inner.layer(req))
+    }
+}
+
+/// A [`Layer`] from the closure, returned by [`layer_fn`].
+#[derive(Clone, Copy, Debug)]
+pub struct LayerFn<F> {
+    f: F,
+}
+
+/// Returns a [`Layer`] which wraps requests with the given closure, like
+/// `layer_fn(|req: GetItem| req.timeout(duration))`.
+pub fn layer_fn<F>(f: F) -> LayerFn<F> {
+    LayerFn { f }
+}
+
+impl<F, R, U> Layer<R> for LayerFn<F>
+where
+    F: Fn(R) -> U,
+{
+    type Request = U;
+
+    fn layer(&self, req: R) -> Self::Request {
+        (self.f)(req)
+    }
+}
+
+/// A [`Layer`] which wraps requests with the [`Middleware`].
+#[derive(Clone, Copy, Debug)]
+pub struct MiddlewareLayer<M> {
+    middleware: M,
+}
+
+impl<M> MiddlewareLayer<M> {
+    pub fn new(middleware: M) -> Self {
+        MiddlewareLayer { middleware }
+    }
+}
+
+impl<M, R> Layer<R> for MiddlewareLayer<M>
+where
+    M: Clone,
+{
+    type Request = WithMiddleware<R, M>;
+
+    fn layer(&self, req: R) -> Self::Request {
+        WithMiddleware::new(req, self.middleware.clone())
+    }
+}
+
+/// A builder to compose a stack of layers, applied in the order they were
+/// added; the first one becomes the outermost.
+///
+/// ```
+/// # #[cfg(feature = "tokio-timer")] {
+/// use std::pin::Pin;
+/// # use std::time::Duration;
+///
+/// use adventure::layer::{layer_fn, Middleware, RequestBuilder, WithMiddleware};
+/// use adventure::prelude::*;
+///
+/// #[derive(Clone)]
+/// struct Logging;
+///
+/// impl<R, C> Middleware<R, C> for Logging
+/// where
+///     R: Request<C>,
+/// {
+///     type Response = R::Response;
+///
+///     fn send(&self, req: Pin<&mut R>, client: C) -> Self::Response {
+///         println!("sending a request");
+///         req.send(client)
+///     }
+/// }
+///
+/// # #[derive(Clone)] struct ListServices;
+/// # impl BaseRequest for ListServices { type Ok = (); type Error = String; }
+/// # impl RetriableRequest for ListServices {
+/// #     fn should_retry(&self, _: &String, _: Duration) -> bool { true }
+/// # }
+/// // each attempt of retrials is logged.
+/// let stack = RequestBuilder::new()
+///     .layer(layer_fn(|req: WithMiddleware<ListServices, Logging>| req.retry()))
+///     .middleware(Logging);
+/// let req = stack.request(ListServices);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RequestBuilder<L> {
+    layer: L,
+}
+
+impl RequestBuilder<Identity> {
+    pub fn new() -> Self {
+        RequestBuilder { layer: Identity }
+    }
+}
+
+impl Default for RequestBuilder<Identity> {
+    fn default() -> Self {
+        RequestBuilder::new()
+    }
+}
+
+impl<L> RequestBuilder<L> {
+    /// Adds a layer, which wraps requests before the ones added earlier.
+    pub fn layer<T>(self, layer: T) -> RequestBuilder<Stack<T, L>> {
+        RequestBuilder {
+            layer: Stack::new(layer, self.layer),
+        }
+    }
+
+    /// Adds a [`Middleware`] layer.
+    pub fn middleware<M>(self, middleware: M) -> RequestBuilder<Stack<MiddlewareLayer<M>, L>> {
+        self.layer(MiddlewareLayer::new(middleware))
+    }
+
+    /// Wraps the given request with the layers.
+    pub fn request<R>(&self, req: R) -> L::Request
+    where
+        L: Layer<R>,
+    {
+        self.layer.layer(req)
+    }
+
+    /// Returns the composed layer.
+    pub fn into_inner(self) -> L {
+        self.layer
+    }
+}
+
+impl<L, R> Layer<R> for RequestBuilder<L>
+where
+    L: Layer<R>,
+{
+    type Request = L::Request;
+
+    fn layer(&self, req: R) -> Self::Request {
+        self.layer.layer(req)
+    }
+}
+
+/// A cross-cutting concern on sending requests, like logging or metrics.
+///
+/// A middleware is applied with
+/// [`with_middleware`](crate::util::RequestExt::with_middleware) or
+/// [`MiddlewareLayer`], and the wrapped request implements the same traits as
+/// the underlying one, including [`PagedRequest`](crate::paginator::PagedRequest)
+/// and `RetriableRequest`. Therefore, a middleware cannot change the type of
+/// results.
+pub trait Middleware<R, C>
+where
+    R: Request<C>,
+{
+    /// The type of responses wrapped by this middleware.
+    type Response: Response<Ok = R::Ok, Error = R::Error>;
+
+    /// Sends the given request to the client, usually by calling
+    /// [`send`](crate::request::Request::send) on it.
+    fn send(&self, req: Pin<&mut R>, client: C) -> Self::Response;
+}
+
+/// Request for [`with_middleware`](crate::util::RequestExt::with_middleware)
+/// combinator.
+#[derive(Clone)]
+pub struct WithMiddleware<R, M> {
+    inner: R,
+    middleware: M,
+}
+
+impl<R, M> WithMiddleware<R, M> {
+    pub(crate) fn new(req: R, middleware: M) -> Self {
+        WithMiddleware {
+            inner: req,
+            middleware,
+        }
+    }
+
+    /// Returns the middleware.
+    pub fn middleware(&self) -> &M {
+        &self.middleware
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, M> BaseRequest for WithMiddleware<R, M>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, M, C> OneshotRequest<C> for WithMiddleware<R, M>
+where
+    R: Request<C>,
+    M: Middleware<R, C>,
+{
+    type Response = M::Response;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let WithMiddleware { inner, middleware } = self;
+        pin_mut!(inner);
+        middleware.send(inner, client)
+    }
+}
+
+impl<R, M, C> Request<C> for WithMiddleware<R, M>
+where
+    R: Request<C>,
+    M: Middleware<R, C>,
+{
+    type Response = M::Response;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        // `inner` is structurally pinned, while `middleware` is not.
+        let this = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+        this.middleware.send(inner, client)
+    }
+}
+
+impl<R, M> Unpin for WithMiddleware<R, M> where R: Unpin {}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::WithMiddleware;
+    use crate::retry::RetriableRequest;
+
+    impl<R, M> RetriableRequest for WithMiddleware<R, M>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::WithMiddleware;
+    use crate::paginator::PagedRequest;
+
+    impl<R, M> PagedRequest for WithMiddleware<R, M>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+mod impl_hedge {
+    use super::WithMiddleware;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, M> IdempotentRequest for WithMiddleware<R, M> where R: IdempotentRequest {}
+}
+
+#[cfg(feature = "std")]
+mod impl_cache {
+    use super::WithMiddleware;
+    use crate::cache::RequestKey;
+
+    impl<R, M> RequestKey for WithMiddleware<R, M>
+    where
+        R: RequestKey,
+    {
+        type Key = R::Key;
+
+        fn key(&self) -> Self::Key {
+            self.inner.key()
+        }
+    }
+}
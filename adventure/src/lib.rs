@@ -81,24 +81,76 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+pub mod and_then;
+pub mod either;
+pub mod layer;
+pub mod map;
 pub mod oneshot;
+pub mod or_else;
 pub mod paginator;
 pub mod prelude;
+pub mod race;
 pub mod repeat;
 pub mod request;
+pub mod request_fn;
 pub mod response;
+pub mod util;
+pub mod zip;
 
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(all(feature = "std", feature = "backoff"))]
+pub mod batcher;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "alloc")]
+pub mod boxed;
+#[cfg(feature = "std")]
+pub mod breaker;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod cancel;
+#[cfg(all(feature = "std", feature = "backoff"))]
+pub mod executor;
+#[cfg(feature = "alloc")]
+pub mod extensions;
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+pub mod hedge;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(all(feature = "std", feature = "backoff"))]
+pub mod rate_limit;
 #[cfg(feature = "backoff")]
 pub mod retry;
+#[cfg(feature = "std")]
+pub mod single_flight;
+#[cfg(feature = "backoff")]
+pub mod timeout;
+#[cfg(feature = "tower")]
+pub mod tower;
 
 #[doc(inline)]
 pub use crate::{
     oneshot::OneshotRequest,
     paginator::{PagedRequest, Paginator},
+    race::race,
     request::{BaseRequest, Request},
+    request_fn::{oneshot_fn, paged_fn, request_fn},
     response::Response,
+    util::{OneshotRequestExt, RequestExt},
+    zip::zip,
 };
 
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::cache::RequestKey;
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+#[doc(inline)]
+pub use crate::hedge::IdempotentRequest;
 #[cfg(feature = "backoff")]
 #[doc(inline)]
 pub use crate::retry::RetriableRequest;
+#[cfg(feature = "derive")]
+#[doc(inline)]
+pub use adventure_derive::{BaseRequest, PagedRequest, Request};
@@ -0,0 +1,277 @@
+//! Adaptors to transform the result of requests.
+use core::pin::Pin;
+
+use futures::future::{self, TryFutureExt};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+
+/// Request for [`map_ok`](crate::util::RequestExt::map_ok) combinator.
+///
+/// Because that [`PagedRequest::advance`](crate::paginator::PagedRequest::advance)
+/// only receives the transformed value, it is forwarded to the inner
+/// request only if the untransformed value can be recovered from it, with
+/// [`with_reverse`](MapOk::with_reverse). Otherwise, map the pages of
+/// [`Paginator`](crate::paginator::Paginator) instead.
+#[derive(Clone)]
+pub struct MapOk<R, F, G = ()> {
+    inner: R,
+    f: F,
+    reverse: G,
+}
+
+impl<R, F> MapOk<R, F> {
+    pub(crate) fn new(req: R, f: F) -> Self {
+        MapOk {
+            inner: req,
+            f,
+            reverse: (),
+        }
+    }
+
+    /// Recovers the untransformed values from the transformed ones with the
+    /// given function, to make this request paginated by the inner request.
+    ///
+    /// The pagination ends when it returns `None`.
+    pub fn with_reverse<G, T>(self, reverse: G) -> MapOk<R, F, G>
+    where
+        R: BaseRequest,
+        F: FnOnce(R::Ok) -> T,
+        G: Fn(&T) -> Option<&R::Ok>,
+    {
+        MapOk {
+            inner: self.inner,
+            f: self.f,
+            reverse,
+        }
+    }
+}
+
+impl<R, F, G> MapOk<R, F, G> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(f: F);
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, F, G, T> BaseRequest for MapOk<R, F, G>
+where
+    R: BaseRequest,
+    F: FnOnce(R::Ok) -> T,
+{
+    type Ok = T;
+    type Error = R::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, F, G, T, C> OneshotRequest<C> for MapOk<R, F, G>
+where
+    R: OneshotRequest<C>,
+    F: FnOnce(R::Ok) -> T,
+{
+    type Response = future::MapOk<R::Response, F>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        self.inner.send_once(client).map_ok(self.f)
+    }
+}
+
+impl<R, F, G, T, C> Request<C> for MapOk<R, F, G>
+where
+    R: Request<C>,
+    F: FnOnce(R::Ok) -> T + Clone,
+{
+    type Response = future::MapOk<R::Response, F>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let f = self.as_mut().f().clone();
+        self.inner().send(client).map_ok(f)
+    }
+}
+
+impl<R, F, G> Unpin for MapOk<R, F, G> where R: Unpin {}
+
+/// Request for [`map_err`](crate::util::RequestExt::map_err) combinator.
+///
+/// Because that [`RetriableRequest::should_retry`](crate::retry::RetriableRequest::should_retry)
+/// only receives the transformed error, it is forwarded to the inner
+/// request only if the untransformed error can be recovered from it, with
+/// [`with_reverse`](MapErr::with_reverse). Otherwise, retry the request
+/// before mapping errors, or use
+/// [`retry_if`](crate::util::RequestExt::retry_if) with a predicate over the
+/// transformed error instead.
+#[derive(Clone)]
+pub struct MapErr<R, F, G = ()> {
+    inner: R,
+    f: F,
+    reverse: G,
+}
+
+impl<R, F> MapErr<R, F> {
+    pub(crate) fn new(req: R, f: F) -> Self {
+        MapErr {
+            inner: req,
+            f,
+            reverse: (),
+        }
+    }
+
+    /// Recovers the untransformed errors from the transformed ones with the
+    /// given function, to make this request retried by the inner request.
+    ///
+    /// Errors are not retried if it returns `None`.
+    pub fn with_reverse<G, E>(self, reverse: G) -> MapErr<R, F, G>
+    where
+        R: BaseRequest,
+        F: FnOnce(R::Error) -> E,
+        G: Fn(&E) -> Option<&R::Error>,
+    {
+        MapErr {
+            inner: self.inner,
+            f: self.f,
+            reverse,
+        }
+    }
+}
+
+impl<R, F, G> MapErr<R, F, G> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(f: F);
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, F, G, E> BaseRequest for MapErr<R, F, G>
+where
+    R: BaseRequest,
+    F: FnOnce(R::Error) -> E,
+{
+    type Ok = R::Ok;
+    type Error = E;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, F, G, E, C> OneshotRequest<C> for MapErr<R, F, G>
+where
+    R: OneshotRequest<C>,
+    F: FnOnce(R::Error) -> E,
+{
+    type Response = future::MapErr<R::Response, F>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        self.inner.send_once(client).map_err(self.f)
+    }
+}
+
+impl<R, F, G, E, C> Request<C> for MapErr<R, F, G>
+where
+    R: Request<C>,
+    F: FnOnce(R::Error) -> E + Clone,
+{
+    type Response = future::MapErr<R::Response, F>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let f = self.as_mut().f().clone();
+        self.inner().send(client).map_err(f)
+    }
+}
+
+impl<R, F, G> Unpin for MapErr<R, F, G> where R: Unpin {}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::{MapErr, MapOk};
+    use crate::retry::RetriableRequest;
+
+    impl<R, F, G, T> RetriableRequest for MapOk<R, F, G>
+    where
+        R: RetriableRequest,
+        F: FnOnce(R::Ok) -> T,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+
+    impl<R, F, G, E> RetriableRequest for MapErr<R, F, G>
+    where
+        R: RetriableRequest,
+        F: FnOnce(R::Error) -> E,
+        G: Fn(&E) -> Option<&R::Error>,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            match (self.reverse)(error) {
+                Some(e) => self.inner.should_retry(e, next_interval),
+                None => false,
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+mod impl_hedge {
+    use super::{MapErr, MapOk};
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, F, G, T> IdempotentRequest for MapOk<R, F, G>
+    where
+        R: IdempotentRequest,
+        F: FnOnce(R::Ok) -> T,
+    {
+    }
+
+    impl<R, F, G, E> IdempotentRequest for MapErr<R, F, G>
+    where
+        R: IdempotentRequest,
+        F: FnOnce(R::Error) -> E,
+    {
+    }
+}
+
+mod impl_paginator {
+    use super::{MapErr, MapOk};
+    use crate::paginator::PagedRequest;
+
+    impl<R, F, G, T> PagedRequest for MapOk<R, F, G>
+    where
+        R: PagedRequest,
+        F: FnOnce(R::Ok) -> T,
+        G: Fn(&T) -> Option<&R::Ok>,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            match (self.reverse)(response) {
+                Some(ok) => self.inner.advance(ok),
+                None => false,
+            }
+        }
+    }
+
+    impl<R, F, G, E> PagedRequest for MapErr<R, F, G>
+    where
+        R: PagedRequest,
+        F: FnOnce(R::Error) -> E,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use pin_utils::pin_mut;
 
-use crate::repeat::Repeat;
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
 use crate::request::{BaseRequest, Request};
 use crate::response::Response;
 
@@ -11,13 +12,6 @@ pub trait OneshotRequest<C>: BaseRequest {
 
     /// Send this request to the given client, by consuming itself.
     fn send_once(self, client: C) -> Self::Response;
-
-    fn repeat(self) -> Repeat<Self>
-    where
-        Self: Clone,
-    {
-        Repeat::from(self)
-    }
 }
 
 /// An [`OneshotRequest`] adaptor for types that implements [`Request`].
@@ -38,6 +32,11 @@ where
 {
     type Ok = R::Ok;
     type Error = R::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
 }
 
 impl<R, C> OneshotRequest<C> for Oneshot<R>
@@ -72,7 +71,7 @@ mod impl_retry {
 
 #[cfg(feature = "alloc")]
 mod feature_alloc {
-    use alloc::boxed::Box;
+    use alloc::{boxed::Box, rc::Rc, sync::Arc};
 
     use super::*;
 
@@ -86,4 +85,30 @@ mod feature_alloc {
             inner.send_once(client)
         }
     }
+
+    /// Sends the shared request itself if there is no other reference to it,
+    /// or a clone of it.
+    impl<R, C> OneshotRequest<C> for Rc<R>
+    where
+        R: OneshotRequest<C> + Clone,
+    {
+        type Response = R::Response;
+        fn send_once(self, client: C) -> Self::Response {
+            let inner = Rc::try_unwrap(self).unwrap_or_else(|shared| (*shared).clone());
+            inner.send_once(client)
+        }
+    }
+
+    /// Sends the shared request itself if there is no other reference to it,
+    /// or a clone of it.
+    impl<R, C> OneshotRequest<C> for Arc<R>
+    where
+        R: OneshotRequest<C> + Clone,
+    {
+        type Response = R::Response;
+        fn send_once(self, client: C) -> Self::Response {
+            let inner = Arc::try_unwrap(self).unwrap_or_else(|shared| (*shared).clone());
+            inner.send_once(client)
+        }
+    }
 }
@@ -0,0 +1,187 @@
+//! An adaptor to send an alternative request on failures.
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// Request for [`or_else`](crate::util::RequestExt::or_else) combinator.
+///
+/// The given closure decides whether to fall back, by returning the
+/// alternative request, or to give up with an error. Combined with
+/// [`retry`](crate::util::RequestExt::retry), it can fall back only
+/// after retrials have exhausted, like
+/// `req.retry().or_else(|e| if e.is_timeout() { Ok(replica) } else { Err(e) })`.
+#[derive(Clone)]
+pub struct OrElse<R, F> {
+    inner: R,
+    f: F,
+}
+
+impl<R, F> OrElse<R, F> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(f: F);
+
+    pub(crate) fn new(req: R, f: F) -> Self {
+        OrElse { inner: req, f }
+    }
+
+    /// Consumes this adaptor, returning the primary request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, F, U> BaseRequest for OrElse<R, F>
+where
+    R: BaseRequest,
+    F: FnOnce(R::Error) -> Result<U, U::Error>,
+    U: BaseRequest<Ok = R::Ok>,
+{
+    type Ok = R::Ok;
+    type Error = U::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, F, U, C> OneshotRequest<C> for OrElse<R, F>
+where
+    R: OneshotRequest<C>,
+    F: FnOnce(R::Error) -> Result<U, U::Error>,
+    U: OneshotRequest<C, Ok = R::Ok>,
+    C: Clone,
+{
+    type Response = OrElseResponse<R::Response, F, U, C>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let primary = self.inner.send_once(client.clone());
+        OrElseResponse::new(primary, self.f, client)
+    }
+}
+
+impl<R, F, U, C> Request<C> for OrElse<R, F>
+where
+    R: Request<C>,
+    F: FnOnce(R::Error) -> Result<U, U::Error> + Clone,
+    U: OneshotRequest<C, Ok = R::Ok>,
+    C: Clone,
+{
+    type Response = OrElseResponse<R::Response, F, U, C>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let f = self.as_mut().f().clone();
+        let primary = self.inner().send(client.clone());
+        OrElseResponse::new(primary, f, client)
+    }
+}
+
+impl<R, F> Unpin for OrElse<R, F> where R: Unpin {}
+
+/// Response for [`or_else`](crate::util::RequestExt::or_else) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct OrElseResponse<T, F, U, C>
+where
+    U: OneshotRequest<C>,
+{
+    primary: Option<T>,
+    fallback: Option<U::Response>,
+    f: Option<F>,
+    client: Option<C>,
+}
+
+impl<T, F, U, C> OrElseResponse<T, F, U, C>
+where
+    U: OneshotRequest<C>,
+{
+    unsafe_pinned!(primary: Option<T>);
+    unsafe_pinned!(fallback: Option<U::Response>);
+    unsafe_unpinned!(f: Option<F>);
+    unsafe_unpinned!(client: Option<C>);
+
+    fn new(primary: T, f: F, client: C) -> Self {
+        OrElseResponse {
+            primary: Some(primary),
+            fallback: None,
+            f: Some(f),
+            client: Some(client),
+        }
+    }
+}
+
+impl<T, F, U, C> Unpin for OrElseResponse<T, F, U, C>
+where
+    T: Unpin,
+    U: OneshotRequest<C>,
+    U::Response: Unpin,
+{
+}
+
+impl<T, F, U, C> Future for OrElseResponse<T, F, U, C>
+where
+    T: Response,
+    F: FnOnce(T::Error) -> Result<U, U::Error>,
+    U: OneshotRequest<C, Ok = T::Ok>,
+{
+    type Output = Result<T::Ok, U::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(primary) = self.as_mut().primary().as_pin_mut() {
+            let err = match primary.try_poll(ctx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(ok)) => {
+                    self.as_mut().primary().set(None);
+                    return Poll::Ready(Ok(ok));
+                }
+                Poll::Ready(Err(e)) => e,
+            };
+            self.as_mut().primary().set(None);
+
+            let f = self
+                .as_mut()
+                .f()
+                .take()
+                .expect("the function is kept until the primary response fails");
+            let client = self
+                .as_mut()
+                .client()
+                .take()
+                .expect("the client is kept until the primary response fails");
+            let fallback = match f(err) {
+                Ok(req) => req.send_once(client),
+                Err(e) => return Poll::Ready(Err(e)),
+            };
+            self.as_mut().fallback().set(Some(fallback));
+        }
+
+        match self.as_mut().fallback().as_pin_mut() {
+            Some(fallback) => fallback.try_poll(ctx),
+            None => panic!("OrElseResponse polled after completion"),
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::OrElse;
+    use crate::paginator::PagedRequest;
+    use crate::request::BaseRequest;
+
+    impl<R, F, U> PagedRequest for OrElse<R, F>
+    where
+        R: PagedRequest,
+        F: FnOnce(R::Error) -> Result<U, U::Error>,
+        U: BaseRequest<Ok = R::Ok>,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
@@ -5,6 +5,8 @@ use core::task::{Context, Poll};
 use futures::stream::{FusedStream, Stream};
 use pin_utils::unsafe_pinned;
 
+#[cfg(feature = "std")]
+use crate::cancel::CancellationToken;
 use crate::request::{BaseRequest, Request};
 use crate::response::Response;
 
@@ -13,13 +15,6 @@ pub trait PagedRequest: BaseRequest {
     /// Modify itself to retrive the next response, of return `false` if the
     /// given response was the last one.
     fn advance(&mut self, response: &Self::Ok) -> bool;
-
-    fn paginate<C>(self, client: C) -> Paginator<C, Self>
-    where
-        Self: Request<C> + Sized,
-    {
-        Paginator::new(client, self)
-    }
 }
 
 impl<P> PagedRequest for Pin<P>
@@ -40,6 +35,8 @@ where
     client: C,
     request: Option<R>,
     next: Option<R::Response>,
+    #[cfg(feature = "std")]
+    cancel: Option<CancellationToken>,
 }
 
 impl<C, R> Paginator<C, R>
@@ -55,8 +52,31 @@ where
             client,
             request: Some(request),
             next: None,
+            #[cfg(feature = "std")]
+            cancel: None,
+        }
+    }
+
+    /// Ends the stream after the page in flight, once the given token is
+    /// cancelled.
+    #[cfg(feature = "std")]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    #[cfg(feature = "std")]
+    fn is_cancelled(&self) -> bool {
+        match &self.cancel {
+            Some(token) => token.is_cancelled(),
+            None => false,
         }
     }
+
+    #[cfg(not(feature = "std"))]
+    fn is_cancelled(&self) -> bool {
+        false
+    }
 }
 
 impl<C, R> Unpin for Paginator<C, R>
@@ -76,6 +96,9 @@ where
         ctx: &mut Context<'_>,
     ) -> Poll<Option<Result<R::Ok, R::Error>>> {
         if self.as_mut().next().is_none() {
+            if self.is_cancelled() {
+                self.as_mut().request().set(None);
+            }
             let client = self.client.clone();
             if let Some(request) = self.as_mut().request().as_pin_mut() {
                 let next = request.send(client);
@@ -135,7 +158,7 @@ where
 
 #[cfg(feature = "alloc")]
 mod feature_alloc {
-    use alloc::boxed::Box;
+    use alloc::{boxed::Box, rc::Rc, sync::Arc};
 
     use super::*;
 
@@ -147,4 +170,26 @@ mod feature_alloc {
             (**self).advance(response)
         }
     }
+
+    /// Advances a clone of the shared request, if there are other references
+    /// to it. They are not affected by the pagination.
+    impl<R> PagedRequest for Rc<R>
+    where
+        R: PagedRequest + Clone,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            Rc::make_mut(self).advance(response)
+        }
+    }
+
+    /// Advances a clone of the shared request, if there are other references
+    /// to it. They are not affected by the pagination.
+    impl<R> PagedRequest for Arc<R>
+    where
+        R: PagedRequest + Clone,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            Arc::make_mut(self).advance(response)
+        }
+    }
 }
@@ -0,0 +1,432 @@
+//! Pools of clients, to balance requests over many endpoints.
+use core::future::Future;
+use core::hash::{BuildHasher, Hasher};
+use core::ops::Deref;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::breaker::BreakerPredicate;
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::paginator::PagedRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// Strategies to pick a client from a [`ClientPool`] for each request.
+///
+/// The default strategy is [`Balance::RoundRobin`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Balance {
+    /// Picks clients in turn.
+    RoundRobin,
+    /// Picks the client with the fewest responses in flight.
+    LeastInFlight,
+    /// Picks two clients at random, and then the one with fewer responses
+    /// in flight.
+    PowerOfTwoChoices,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Ejection {
+    ratio: f64,
+    window: usize,
+    duration: Duration,
+}
+
+#[derive(Debug)]
+struct Member<C> {
+    client: C,
+    in_flight: AtomicUsize,
+    health: Mutex<Health>,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    outcomes: VecDeque<bool>,
+    ejected_until: Option<Instant>,
+}
+
+/// A set of clients to send the same requests, which can be used as the
+/// client of [`pooled`](crate::util::RequestExt::pooled) requests.
+///
+/// A client is picked for each request by the [`Balance`] strategy. By
+/// default, a client is ejected from the pool for 30 seconds if at least
+/// half of its last 10 responses have failed. If all clients are ejected,
+/// requests are sent to them anyway.
+#[derive(Debug)]
+pub struct ClientPool<C> {
+    members: Vec<Member<C>>,
+    balance: Balance,
+    ejection: Option<Ejection>,
+    next: AtomicUsize,
+    random: RandomState,
+}
+
+impl<C> ClientPool<C> {
+    /// Creates a pool of the given clients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no clients.
+    pub fn new<I>(clients: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+    {
+        let members: Vec<_> = clients
+            .into_iter()
+            .map(|client| Member {
+                client,
+                in_flight: AtomicUsize::new(0),
+                health: Default::default(),
+            })
+            .collect();
+        assert!(!members.is_empty(), "pool must have clients");
+        ClientPool {
+            members,
+            balance: Balance::RoundRobin,
+            ejection: Some(Ejection {
+                ratio: 0.5,
+                window: 10,
+                duration: Duration::from_secs(30),
+            }),
+            next: AtomicUsize::new(0),
+            random: RandomState::new(),
+        }
+    }
+
+    /// Sets the strategy to pick a client for each request.
+    pub fn with_balance(self, balance: Balance) -> Self {
+        ClientPool { balance, ..self }
+    }
+
+    /// Ejects a client for the given duration if the ratio of failures
+    /// among its last `window` responses has reached the given ratio.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ratio is not in `(0, 1]`, or the window is zero.
+    pub fn with_ejection(self, ratio: f64, window: usize, duration: Duration) -> Self {
+        assert!(
+            ratio > 0.0 && ratio <= 1.0,
+            "ratio must be greater than 0 and at most 1"
+        );
+        assert!(window > 0, "window must be positive");
+        ClientPool {
+            ejection: Some(Ejection {
+                ratio,
+                window,
+                duration,
+            }),
+            ..self
+        }
+    }
+
+    /// Never ejects clients, regardless of their failures.
+    pub fn without_ejection(self) -> Self {
+        ClientPool {
+            ejection: None,
+            ..self
+        }
+    }
+
+    /// Returns the number of clients in this pool.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `false`, as a pool can't be created without clients.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Returns the client at the given index.
+    pub fn get(&self, index: usize) -> Option<&C> {
+        self.members.get(index).map(|m| &m.client)
+    }
+
+    /// Returns the number of responses in flight from the client at the
+    /// given index.
+    pub fn in_flight(&self, index: usize) -> Option<usize> {
+        self.members.get(index).map(Member::in_flight)
+    }
+
+    /// Returns whether the client at the given index is ejected now.
+    pub fn is_ejected(&self, index: usize) -> Option<bool> {
+        let now = Instant::now();
+        self.members.get(index).map(|m| m.is_ejected(now))
+    }
+
+    pub(crate) fn acquire(&self) -> (usize, &C) {
+        let now = Instant::now();
+        let mut available: Vec<_> = (0..self.members.len())
+            .filter(|&i| !self.members[i].is_ejected(now))
+            .collect();
+        if available.is_empty() {
+            available.extend(0..self.members.len());
+        }
+
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        let index = match self.balance {
+            Balance::RoundRobin => available[n % available.len()],
+            Balance::LeastInFlight => {
+                let start = n % available.len();
+                let (tail, head) = available.split_at(start);
+                head.iter()
+                    .chain(tail)
+                    .copied()
+                    .min_by_key(|&i| self.members[i].in_flight())
+                    .expect("clients are always available")
+            }
+            Balance::PowerOfTwoChoices => {
+                let a = available[self.random(n, 0) % available.len()];
+                let b = available[self.random(n, 1) % available.len()];
+                if self.members[b].in_flight() < self.members[a].in_flight() {
+                    b
+                } else {
+                    a
+                }
+            }
+        };
+
+        let member = &self.members[index];
+        member.in_flight.fetch_add(1, Ordering::SeqCst);
+        (index, &member.client)
+    }
+
+    pub(crate) fn release(&self, index: usize, failed: Option<bool>) {
+        let member = &self.members[index];
+        member.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        if let (Some(ejection), Some(failed)) = (self.ejection, failed) {
+            let mut health = member.lock();
+            if health.outcomes.len() == ejection.window {
+                health.outcomes.pop_front();
+            }
+            health.outcomes.push_back(failed);
+            if health.outcomes.len() == ejection.window {
+                let failures = health.outcomes.iter().filter(|&&f| f).count();
+                if failures as f64 >= ejection.ratio * ejection.window as f64 {
+                    health.outcomes.clear();
+                    health.ejected_until = Some(Instant::now() + ejection.duration);
+                }
+            }
+        }
+    }
+
+    fn random(&self, n: usize, salt: usize) -> usize {
+        let mut hasher = self.random.build_hasher();
+        hasher.write_usize(n);
+        hasher.write_usize(salt);
+        hasher.finish() as usize
+    }
+}
+
+impl<C> Member<C> {
+    fn lock(&self) -> MutexGuard<'_, Health> {
+        self.health.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    fn is_ejected(&self, now: Instant) -> bool {
+        match self.lock().ejected_until {
+            Some(until) => until > now,
+            None => false,
+        }
+    }
+}
+
+/// Request for [`pooled`](crate::util::RequestExt::pooled) combinator.
+#[derive(Clone)]
+pub struct Pooled<R, P = ()> {
+    inner: R,
+    pred: P,
+}
+
+impl<R> Pooled<R> {
+    pub(crate) fn new(req: R) -> Self {
+        Pooled {
+            inner: req,
+            pred: (),
+        }
+    }
+}
+
+impl<R, P> Pooled<R, P> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(pred: P);
+
+    /// Sets the predicate to decide which errors are counted as failures of
+    /// the client.
+    ///
+    /// By default, all errors are counted.
+    pub fn with_predicate<Q>(self, pred: Q) -> Pooled<R, Q>
+    where
+        R: BaseRequest,
+        Q: BreakerPredicate<R::Error>,
+    {
+        Pooled {
+            inner: self.inner,
+            pred,
+        }
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, P> BaseRequest for Pooled<R, P>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, P, S, C> OneshotRequest<S> for Pooled<R, P>
+where
+    R: OneshotRequest<C>,
+    P: BreakerPredicate<R::Error>,
+    S: Deref<Target = ClientPool<C>>,
+    C: Clone,
+{
+    type Response = PooledResponse<R::Response, S, P>;
+
+    fn send_once(self, pool: S) -> Self::Response {
+        let (index, client) = pool.acquire();
+        let inner = self.inner.send_once(client.clone());
+        PooledResponse::new(inner, pool, index, self.pred)
+    }
+}
+
+impl<R, P, S, C> Request<S> for Pooled<R, P>
+where
+    R: Request<C>,
+    P: BreakerPredicate<R::Error> + Clone,
+    S: Deref<Target = ClientPool<C>>,
+    C: Clone,
+{
+    type Response = PooledResponse<R::Response, S, P>;
+
+    fn send(mut self: Pin<&mut Self>, pool: S) -> Self::Response {
+        let (index, client) = pool.acquire();
+        let client = client.clone();
+        let pred = self.as_mut().pred().clone();
+        let inner = self.inner().send(client);
+        PooledResponse::new(inner, pool, index, pred)
+    }
+}
+
+impl<R, P> PagedRequest for Pooled<R, P>
+where
+    R: PagedRequest,
+{
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        self.inner.advance(response)
+    }
+}
+
+impl<R, P> Unpin for Pooled<R, P> where R: Unpin {}
+
+/// Response for [`pooled`](crate::util::RequestExt::pooled) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct PooledResponse<F, S, P> {
+    inner: F,
+    pool: Option<S>,
+    index: usize,
+    pred: P,
+    release: fn(&S, usize, Option<bool>),
+}
+
+impl<F, S, P> PooledResponse<F, S, P> {
+    unsafe_pinned!(inner: F);
+    unsafe_unpinned!(pool: Option<S>);
+
+    fn new<C>(inner: F, pool: S, index: usize, pred: P) -> Self
+    where
+        S: Deref<Target = ClientPool<C>>,
+    {
+        PooledResponse {
+            inner,
+            pool: Some(pool),
+            index,
+            pred,
+            release: |pool, index, failed| pool.release(index, failed),
+        }
+    }
+
+    /// Returns the index of the client in the pool, which this response is
+    /// from.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<F, S, P> Unpin for PooledResponse<F, S, P> where F: Unpin {}
+
+impl<F, S, P> Future for PooledResponse<F, S, P>
+where
+    F: Response,
+    P: BreakerPredicate<F::Error>,
+{
+    type Output = Result<F::Ok, F::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = match self.as_mut().inner().try_poll(ctx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(result) => result,
+        };
+        let failed = match &result {
+            Ok(_) => false,
+            Err(e) => self.pred.is_failure(e),
+        };
+        let index = self.index;
+        let release = self.release;
+        if let Some(pool) = self.as_mut().pool().take() {
+            release(&pool, index, Some(failed));
+        }
+        Poll::Ready(result)
+    }
+}
+
+impl<F, S, P> Drop for PooledResponse<F, S, P> {
+    fn drop(&mut self) {
+        // responses dropped before completion are not counted as failures.
+        if let Some(pool) = self.pool.take() {
+            (self.release)(&pool, self.index, None);
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::Pooled;
+    use crate::retry::RetriableRequest;
+
+    impl<R, P> RetriableRequest for Pooled<R, P>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
@@ -3,9 +3,16 @@
 //! This module is intended to be included by `use adventure::prelude::*;`,
 //! to access the various traits and methods mostly will be used.
 
+#[cfg(feature = "std")]
+pub use crate::cache::RequestKey;
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+pub use crate::hedge::IdempotentRequest;
 pub use crate::oneshot::OneshotRequest;
 pub use crate::paginator::PagedRequest;
 pub use crate::request::{BaseRequest, Request};
 pub use crate::response::Response;
 #[cfg(feature = "backoff")]
 pub use crate::retry::RetriableRequest;
+pub use crate::util::{OneshotRequestExt, RequestExt};
+#[cfg(feature = "derive")]
+pub use adventure_derive::{BaseRequest, PagedRequest, Request};
@@ -0,0 +1,164 @@
+//! An adaptor to send two requests concurrently, and to take the first
+//! successful response.
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use pin_utils::unsafe_pinned;
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// Returns a request to send both of the given requests concurrently to the
+/// same client, which results in the first successful value of them.
+///
+/// The other response is dropped as soon as one has succeeded. If both of
+/// them have failed, the error of the last one is returned.
+pub fn race<A, B>(a: A, b: B) -> Race<A, B>
+where
+    A: BaseRequest,
+    B: BaseRequest<Ok = A::Ok, Error = A::Error>,
+{
+    Race { a, b }
+}
+
+/// Request for [`race`].
+///
+/// The extensions of the first request are used for this request.
+#[derive(Clone)]
+pub struct Race<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Race<A, B> {
+    unsafe_pinned!(a: A);
+    unsafe_pinned!(b: B);
+
+    /// Consumes this adaptor, returning the underlying requests.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> BaseRequest for Race<A, B>
+where
+    A: BaseRequest,
+    B: BaseRequest<Ok = A::Ok, Error = A::Error>,
+{
+    type Ok = A::Ok;
+    type Error = A::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.a.extensions()
+    }
+}
+
+impl<A, B, C> OneshotRequest<C> for Race<A, B>
+where
+    A: OneshotRequest<C>,
+    B: OneshotRequest<C, Ok = A::Ok, Error = A::Error>,
+    C: Clone,
+{
+    type Response = RaceResponse<A::Response, B::Response>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let a = self.a.send_once(client.clone());
+        RaceResponse::new(a, self.b.send_once(client))
+    }
+}
+
+impl<A, B, C> Request<C> for Race<A, B>
+where
+    A: Request<C>,
+    B: Request<C, Ok = A::Ok, Error = A::Error>,
+    C: Clone,
+{
+    type Response = RaceResponse<A::Response, B::Response>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let a = self.as_mut().a().send(client.clone());
+        RaceResponse::new(a, self.b().send(client))
+    }
+}
+
+impl<A, B> Unpin for Race<A, B>
+where
+    A: Unpin,
+    B: Unpin,
+{
+}
+
+/// Response for [`race`].
+#[must_use = "responses do nothing unless polled"]
+pub struct RaceResponse<A, B> {
+    a: Option<A>,
+    b: Option<B>,
+}
+
+impl<A, B> RaceResponse<A, B> {
+    unsafe_pinned!(a: Option<A>);
+    unsafe_pinned!(b: Option<B>);
+
+    fn new(a: A, b: B) -> Self {
+        RaceResponse {
+            a: Some(a),
+            b: Some(b),
+        }
+    }
+}
+
+impl<A, B> Unpin for RaceResponse<A, B>
+where
+    A: Unpin,
+    B: Unpin,
+{
+}
+
+impl<A, B> Future for RaceResponse<A, B>
+where
+    A: Response,
+    B: Response<Ok = A::Ok, Error = A::Error>,
+{
+    type Output = Result<A::Ok, A::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(a) = self.as_mut().a().as_pin_mut() {
+            match a.try_poll(ctx) {
+                Poll::Pending => {}
+                Poll::Ready(Ok(ok)) => {
+                    self.as_mut().b().set(None);
+                    return Poll::Ready(Ok(ok));
+                }
+                Poll::Ready(Err(e)) => {
+                    self.as_mut().a().set(None);
+                    if self.b.is_none() {
+                        return Poll::Ready(Err(e));
+                    }
+                }
+            }
+        }
+
+        if let Some(b) = self.as_mut().b().as_pin_mut() {
+            match b.try_poll(ctx) {
+                Poll::Pending => {}
+                Poll::Ready(Ok(ok)) => {
+                    self.as_mut().a().set(None);
+                    return Poll::Ready(Ok(ok));
+                }
+                Poll::Ready(Err(e)) => {
+                    self.as_mut().b().set(None);
+                    if self.a.is_none() {
+                        return Poll::Ready(Err(e));
+                    }
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
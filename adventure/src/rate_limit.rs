@@ -0,0 +1,302 @@
+//! Limiting the rate of requests, to avoid being throttled by servers.
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::breaker::{Clock, SystemClock};
+use crate::extensions::Extensions;
+use crate::oneshot::{Oneshot, OneshotRequest};
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+use crate::retry::Timer;
+
+#[cfg(feature = "tokio-timer")]
+use crate::retry::TokioTimer;
+
+#[cfg(feature = "tokio-timer")]
+pub type RateLimitedTokio<R, L> = RateLimited<R, L, TokioTimer>;
+
+/// A policy to decide when requests are allowed to be sent.
+pub trait RateLimiter {
+    /// Takes a permit to send a request, returning how long to wait before
+    /// sending it.
+    fn acquire(&self) -> Duration;
+}
+
+impl<L> RateLimiter for &L
+where
+    L: RateLimiter + ?Sized,
+{
+    fn acquire(&self) -> Duration {
+        (**self).acquire()
+    }
+}
+
+impl<L> RateLimiter for Arc<L>
+where
+    L: RateLimiter + ?Sized,
+{
+    fn acquire(&self) -> Duration {
+        (**self).acquire()
+    }
+}
+
+/// A [`RateLimiter`] with the token bucket algorithm.
+///
+/// The bucket holds at most `capacity` permits, and a permit is refilled for
+/// each `interval`. Wrap it in an [`Arc`] to share a quota with requests of
+/// different types.
+///
+/// The refill is measured by a [`Clock`], which reads the system time by
+/// default.
+#[derive(Debug)]
+pub struct TokenBucket<K = SystemClock> {
+    interval: Duration,
+    burst: Duration,
+    // the time when the next permit will be available, if the bucket was
+    // empty; the bucket is full as long as it is not later than `now - burst`.
+    next: Mutex<Instant>,
+    clock: K,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket with the given capacity and refill interval.
+    pub fn new(capacity: u32, interval: Duration) -> Self {
+        TokenBucket::with_clock(capacity, interval, SystemClock)
+    }
+}
+
+impl<K> TokenBucket<K>
+where
+    K: Clock,
+{
+    /// Creates a full bucket measuring its refill with the given clock.
+    pub fn with_clock(capacity: u32, interval: Duration, clock: K) -> Self {
+        assert!(capacity > 0, "capacity of the bucket must be positive");
+        TokenBucket {
+            interval,
+            burst: interval * (capacity - 1),
+            next: Mutex::new(clock.now()),
+            clock,
+        }
+    }
+}
+
+impl<K> RateLimiter for TokenBucket<K>
+where
+    K: Clock,
+{
+    fn acquire(&self) -> Duration {
+        let now = self.clock.now();
+        let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+        if *next < now {
+            *next = now;
+        }
+        let wait = next
+            .checked_duration_since(now + self.burst)
+            .unwrap_or_default();
+        *next += self.interval;
+        wait
+    }
+}
+
+/// Request for [`rate_limit`](crate::util::RequestExt::rate_limit)
+/// combinator.
+///
+/// A permit is taken from the limiter whenever the request is sent, even if
+/// the response is dropped before it is actually sent. If the timer has gone
+/// while waiting, the request is sent immediately.
+#[derive(Clone)]
+pub struct RateLimited<R, L, T> {
+    inner: R,
+    limiter: L,
+    timer: T,
+}
+
+impl<R, L, T> RateLimited<R, L, T> {
+    unsafe_unpinned!(timer: T);
+
+    pub(crate) fn new(req: R, limiter: L, timer: T) -> Self {
+        RateLimited {
+            inner: req,
+            limiter,
+            timer,
+        }
+    }
+
+    /// Returns the limiter used by this request.
+    pub fn limiter(&self) -> &L {
+        &self.limiter
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+fn wait_for<T: Timer>(timer: &mut T, interval: Duration) -> Option<T::Delay> {
+    if interval == Duration::default() {
+        None
+    } else {
+        Some(timer.expires_in(interval))
+    }
+}
+
+impl<R, L, T> BaseRequest for RateLimited<R, L, T>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, L, T, C> OneshotRequest<C> for RateLimited<R, L, T>
+where
+    R: OneshotRequest<C>,
+    L: RateLimiter,
+    T: Timer,
+{
+    type Response = RateLimitedResponse<R, T::Delay, C>;
+
+    fn send_once(mut self, client: C) -> Self::Response {
+        let interval = self.limiter.acquire();
+        let wait = wait_for(&mut self.timer, interval);
+        RateLimitedResponse::new(self.inner, wait, client)
+    }
+}
+
+impl<R, L, T, C> Request<C> for RateLimited<R, L, T>
+where
+    R: Request<C> + Clone,
+    L: RateLimiter,
+    T: Timer,
+{
+    type Response = RateLimitedResponse<Oneshot<R>, T::Delay, C>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let interval = self.limiter.acquire();
+        let wait = wait_for(self.as_mut().timer(), interval);
+        let req = Oneshot::from(self.inner.clone());
+        RateLimitedResponse::new(req, wait, client)
+    }
+}
+
+/// Response for [`rate_limit`](crate::util::RequestExt::rate_limit)
+/// combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct RateLimitedResponse<R, D, C>
+where
+    R: OneshotRequest<C>,
+{
+    request: Option<R>,
+    client: Option<C>,
+    wait: Option<D>,
+    response: Option<R::Response>,
+}
+
+impl<R, D, C> RateLimitedResponse<R, D, C>
+where
+    R: OneshotRequest<C>,
+{
+    unsafe_unpinned!(request: Option<R>);
+    unsafe_unpinned!(client: Option<C>);
+    unsafe_pinned!(wait: Option<D>);
+    unsafe_pinned!(response: Option<R::Response>);
+
+    fn new(request: R, wait: Option<D>, client: C) -> Self {
+        RateLimitedResponse {
+            request: Some(request),
+            client: Some(client),
+            wait,
+            response: None,
+        }
+    }
+}
+
+impl<R, D, C> Unpin for RateLimitedResponse<R, D, C>
+where
+    R: OneshotRequest<C>,
+    R::Response: Unpin,
+    D: Unpin,
+{
+}
+
+impl<R, D, C> Future for RateLimitedResponse<R, D, C>
+where
+    R: OneshotRequest<C>,
+    D: Response,
+{
+    type Output = Result<R::Ok, R::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(wait) = self.as_mut().wait().as_pin_mut() {
+            if wait.try_poll(ctx).is_pending() {
+                return Poll::Pending;
+            }
+            self.as_mut().wait().set(None);
+        }
+
+        if let Some(request) = self.as_mut().request().take() {
+            let client = self
+                .as_mut()
+                .client()
+                .take()
+                .expect("the client is kept until the request is sent");
+            self.as_mut()
+                .response()
+                .set(Some(request.send_once(client)));
+        }
+
+        match self.as_mut().response().as_pin_mut() {
+            Some(response) => response.try_poll(ctx),
+            None => panic!("RateLimitedResponse polled after completion"),
+        }
+    }
+}
+
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::RateLimited;
+    use crate::retry::RetriableRequest;
+
+    impl<R, L, T> RetriableRequest for RateLimited<R, L, T>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::RateLimited;
+    use crate::paginator::PagedRequest;
+
+    impl<R, L, T> PagedRequest for RateLimited<R, L, T>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
+
+mod impl_hedge {
+    use super::RateLimited;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, L, T> IdempotentRequest for RateLimited<R, L, T> where R: IdempotentRequest {}
+}
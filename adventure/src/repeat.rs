@@ -1,5 +1,7 @@
 use core::pin::Pin;
 
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
 use crate::oneshot::OneshotRequest;
 use crate::request::{BaseRequest, Request};
 
@@ -25,6 +27,11 @@ where
 {
     type Ok = R::Ok;
     type Error = R::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
 }
 
 impl<R, C> OneshotRequest<C> for Repeat<R>
@@ -2,14 +2,10 @@
 use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
 
-use crate::oneshot::Oneshot;
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
 use crate::response::Response;
 
-#[cfg(feature = "backoff")]
-use crate::retry::{Backoff, RetrialPredicate, Retrying, Timer};
-#[cfg(all(feature = "backoff", feature = "tokio-timer"))]
-use crate::retry::{ExponentialBackoff, RetryingTokio};
-
 /// Trait to represent types of the request, and their expected output and
 /// error types.
 pub trait BaseRequest {
@@ -17,6 +13,16 @@ pub trait BaseRequest {
     type Ok;
     /// The type of failures from the corresponding response.
     type Error;
+
+    /// Returns the extensions attached to this request, or to the request
+    /// wrapped by this adaptor.
+    ///
+    /// Middlewares and retrial predicates can read the metadata of requests
+    /// from them, however deeply the requests are wrapped.
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        None
+    }
 }
 
 impl<R> BaseRequest for &R
@@ -25,6 +31,11 @@ where
 {
     type Ok = R::Ok;
     type Error = R::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        (**self).extensions()
+    }
 }
 
 impl<P> BaseRequest for Pin<P>
@@ -34,6 +45,11 @@ where
 {
     type Ok = <P::Target as BaseRequest>::Ok;
     type Error = <P::Target as BaseRequest>::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        (**self).extensions()
+    }
 }
 
 /// A generalized request-response interface, regardless how client works.
@@ -45,38 +61,6 @@ pub trait Request<C>: BaseRequest {
     type Response: Response<Ok = Self::Ok, Error = Self::Error>;
 
     fn send(self: Pin<&mut Self>, client: C) -> Self::Response;
-
-    fn oneshot(self) -> Oneshot<Self>
-    where
-        Self: Sized,
-    {
-        Oneshot::from(self)
-    }
-
-    /// Wrap this request to retry if the given predicate returns `true`.
-    ///
-    /// It should be called within the tokio execution context,
-    /// because the default timer is implemented using [`tokio_timer`].
-    #[cfg(all(feature = "backoff", feature = "tokio-timer"))]
-    fn retry_if<F>(self, pred: F) -> RetryingTokio<Self, ExponentialBackoff, F>
-    where
-        Self: Sized,
-        F: RetrialPredicate<Self>,
-    {
-        RetryingTokio::from_default(self).with_predicate(pred)
-    }
-
-    /// Wrap this request to retry with customizable options, including the timer implementation.
-    #[cfg(feature = "backoff")]
-    fn retry_with_config<T, B, F>(self, timer: T, pred: F, backoff: B) -> Retrying<Self, T, B, F>
-    where
-        Self: Sized,
-        T: Timer + Unpin,
-        B: Backoff,
-        F: RetrialPredicate<Self>,
-    {
-        Retrying::new(self, timer, backoff).with_predicate(pred)
-    }
 }
 
 impl<P, C> Request<C> for Pin<P>
@@ -102,6 +86,10 @@ mod feature_alloc {
     {
         type Ok = R::Ok;
         type Error = R::Error;
+
+        fn extensions(&self) -> Option<&Extensions> {
+            (**self).extensions()
+        }
     }
 
     impl<R> BaseRequest for Rc<R>
@@ -110,6 +98,10 @@ mod feature_alloc {
     {
         type Ok = R::Ok;
         type Error = R::Error;
+
+        fn extensions(&self) -> Option<&Extensions> {
+            (**self).extensions()
+        }
     }
 
     impl<R> BaseRequest for Arc<R>
@@ -118,6 +110,10 @@ mod feature_alloc {
     {
         type Ok = R::Ok;
         type Error = R::Error;
+
+        fn extensions(&self) -> Option<&Extensions> {
+            (**self).extensions()
+        }
     }
 
     impl<R, C> Request<C> for Box<R>
@@ -130,4 +126,28 @@ mod feature_alloc {
             R::send(pinned, client)
         }
     }
+
+    /// Sends the shared request in place if there is no other reference to
+    /// it, or sends a clone of it which is kept by this reference onward.
+    impl<R, C> Request<C> for Rc<R>
+    where
+        R: Request<C> + Clone + Unpin,
+    {
+        type Response = R::Response;
+        fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+            Pin::new(Rc::make_mut(self.get_mut())).send(client)
+        }
+    }
+
+    /// Sends the shared request in place if there is no other reference to
+    /// it, or sends a clone of it which is kept by this reference onward.
+    impl<R, C> Request<C> for Arc<R>
+    where
+        R: Request<C> + Clone + Unpin,
+    {
+        type Response = R::Response;
+        fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+            Pin::new(Arc::make_mut(self.get_mut())).send(client)
+        }
+    }
 }
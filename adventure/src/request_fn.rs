@@ -0,0 +1,255 @@
+//! Requests built from closures, for ad hoc calls without declaring types.
+use core::marker::PhantomData;
+use core::pin::Pin;
+#[cfg(feature = "backoff")]
+use core::time::Duration;
+
+use crate::oneshot::OneshotRequest;
+use crate::paginator::PagedRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// Returns a request which is sent by calling the closure with the client,
+/// like `request_fn(|client: Arc<Client>| async move { client.get().await })`.
+///
+/// The closure may be called many times, to retry or repeat the request.
+pub fn request_fn<F, T, E>(f: F) -> RequestFn<F, T, E> {
+    RequestFn {
+        f,
+        pred: (),
+        _phantom: PhantomData,
+    }
+}
+
+/// Returns a request which is sent just once by calling the closure with the
+/// client.
+pub fn oneshot_fn<F, T, E>(f: F) -> OneshotFn<F, T, E> {
+    OneshotFn {
+        f,
+        _phantom: PhantomData,
+    }
+}
+
+/// Returns a paged request, which fetches a page with the current `state`,
+/// and moves the `state` to the next page with `advance`.
+///
+/// `advance` should return `false` if the given page was the last one.
+pub fn paged_fn<S, F, A, T, E>(state: S, fetch: F, advance: A) -> PagedFn<S, F, A, T, E> {
+    PagedFn {
+        state,
+        fetch,
+        advance,
+        pred: (),
+        _phantom: PhantomData,
+    }
+}
+
+/// Request for [`request_fn`].
+pub struct RequestFn<F, T, E, P = ()> {
+    f: F,
+    pred: P,
+    _phantom: PhantomData<fn() -> Result<T, E>>,
+}
+
+impl<F, T, E> RequestFn<F, T, E> {
+    /// Makes this request a `RetriableRequest`, which retries if the given
+    /// predicate returns `true`.
+    #[cfg(feature = "backoff")]
+    pub fn with_should_retry<P>(self, pred: P) -> RequestFn<F, T, E, P>
+    where
+        P: Fn(&E, Duration) -> bool,
+    {
+        RequestFn {
+            f: self.f,
+            pred,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, T, E, P> Clone for RequestFn<F, T, E, P>
+where
+    F: Clone,
+    P: Clone,
+{
+    fn clone(&self) -> Self {
+        RequestFn {
+            f: self.f.clone(),
+            pred: self.pred.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, T, E, P> BaseRequest for RequestFn<F, T, E, P> {
+    type Ok = T;
+    type Error = E;
+}
+
+impl<F, T, E, P, C, R> OneshotRequest<C> for RequestFn<F, T, E, P>
+where
+    F: FnOnce(C) -> R,
+    R: Response<Ok = T, Error = E>,
+{
+    type Response = R;
+
+    fn send_once(self, client: C) -> Self::Response {
+        (self.f)(client)
+    }
+}
+
+impl<F, T, E, P, C, R> Request<C> for RequestFn<F, T, E, P>
+where
+    F: FnMut(C) -> R,
+    R: Response<Ok = T, Error = E>,
+{
+    type Response = R;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        (self.get_mut().f)(client)
+    }
+}
+
+impl<F, T, E, P> Unpin for RequestFn<F, T, E, P> {}
+
+/// Request for [`oneshot_fn`].
+pub struct OneshotFn<F, T, E> {
+    f: F,
+    _phantom: PhantomData<fn() -> Result<T, E>>,
+}
+
+impl<F, T, E> BaseRequest for OneshotFn<F, T, E> {
+    type Ok = T;
+    type Error = E;
+}
+
+impl<F, T, E, C, R> OneshotRequest<C> for OneshotFn<F, T, E>
+where
+    F: FnOnce(C) -> R,
+    R: Response<Ok = T, Error = E>,
+{
+    type Response = R;
+
+    fn send_once(self, client: C) -> Self::Response {
+        (self.f)(client)
+    }
+}
+
+/// Request for [`paged_fn`].
+pub struct PagedFn<S, F, A, T, E, P = ()> {
+    state: S,
+    fetch: F,
+    advance: A,
+    pred: P,
+    _phantom: PhantomData<fn() -> Result<T, E>>,
+}
+
+impl<S, F, A, T, E> PagedFn<S, F, A, T, E> {
+    /// Makes this request a `RetriableRequest`, which retries if the given
+    /// predicate returns `true`.
+    #[cfg(feature = "backoff")]
+    pub fn with_should_retry<P>(self, pred: P) -> PagedFn<S, F, A, T, E, P>
+    where
+        P: Fn(&E, Duration) -> bool,
+    {
+        PagedFn {
+            state: self.state,
+            fetch: self.fetch,
+            advance: self.advance,
+            pred,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S, F, A, T, E, P> PagedFn<S, F, A, T, E, P> {
+    /// Returns the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S, F, A, T, E, P> Clone for PagedFn<S, F, A, T, E, P>
+where
+    S: Clone,
+    F: Clone,
+    A: Clone,
+    P: Clone,
+{
+    fn clone(&self) -> Self {
+        PagedFn {
+            state: self.state.clone(),
+            fetch: self.fetch.clone(),
+            advance: self.advance.clone(),
+            pred: self.pred.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S, F, A, T, E, P> BaseRequest for PagedFn<S, F, A, T, E, P> {
+    type Ok = T;
+    type Error = E;
+}
+
+impl<S, F, A, T, E, P, C, R> OneshotRequest<C> for PagedFn<S, F, A, T, E, P>
+where
+    F: FnOnce(&S, C) -> R,
+    R: Response<Ok = T, Error = E>,
+{
+    type Response = R;
+
+    fn send_once(self, client: C) -> Self::Response {
+        (self.fetch)(&self.state, client)
+    }
+}
+
+impl<S, F, A, T, E, P, C, R> Request<C> for PagedFn<S, F, A, T, E, P>
+where
+    F: FnMut(&S, C) -> R,
+    R: Response<Ok = T, Error = E>,
+{
+    type Response = R;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        let this = self.get_mut();
+        (this.fetch)(&this.state, client)
+    }
+}
+
+impl<S, F, A, T, E, P> PagedRequest for PagedFn<S, F, A, T, E, P>
+where
+    A: FnMut(&mut S, &T) -> bool,
+{
+    fn advance(&mut self, response: &Self::Ok) -> bool {
+        (self.advance)(&mut self.state, response)
+    }
+}
+
+impl<S, F, A, T, E, P> Unpin for PagedFn<S, F, A, T, E, P> {}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::{PagedFn, RequestFn};
+    use crate::retry::RetriableRequest;
+
+    impl<F, T, E, P> RetriableRequest for RequestFn<F, T, E, P>
+    where
+        P: Fn(&E, Duration) -> bool,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            (self.pred)(error, next_interval)
+        }
+    }
+
+    impl<S, F, A, T, E, P> RetriableRequest for PagedFn<S, F, A, T, E, P>
+    where
+        P: Fn(&E, Duration) -> bool,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            (self.pred)(error, next_interval)
+        }
+    }
+}
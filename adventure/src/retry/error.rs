@@ -13,7 +13,8 @@ pub struct RetryError<E = Infallible> {
 #[derive(Debug)]
 enum RetryErrorKind<E> {
     Aborted(E),
-    Timeout,
+    Timeout(usize),
+    Cancelled,
     #[allow(dead_code)]
     TimerShutdown,
 }
@@ -29,7 +30,8 @@ impl<E: Display> Display for RetryError<E> {
         use RetryErrorKind::*;
         match &self.inner {
             Aborted(e) => e.fmt(f),
-            Timeout => "Timeout reached".fmt(f),
+            Timeout(attempts) => write!(f, "Timeout reached after {} attempts", attempts),
+            Cancelled => "Retrial has been cancelled".fmt(f),
             TimerShutdown => "Timer has gone".fmt(f),
         }
     }
@@ -53,9 +55,16 @@ impl<E> RetryError<E> {
         }
     }
 
-    pub(crate) const fn timeout() -> Self {
+    pub(crate) const fn timeout(attempts: usize) -> Self {
         RetryError {
-            inner: RetryErrorKind::Timeout,
+            inner: RetryErrorKind::Timeout(attempts),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) const fn cancelled() -> Self {
+        RetryError {
+            inner: RetryErrorKind::Cancelled,
         }
     }
 
@@ -89,7 +98,27 @@ impl<E> RetryError<E> {
 
     /// Returns `true` if the error was caused by the operation timed out.
     pub fn is_timeout(&self) -> bool {
-        if let RetryErrorKind::Timeout = &self.inner {
+        if let RetryErrorKind::Timeout(_) = &self.inner {
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the number of attempts made until timed out, if the error was
+    /// caused by the operation timed out.
+    pub fn attempts(&self) -> Option<usize> {
+        if let RetryErrorKind::Timeout(attempts) = &self.inner {
+            Some(*attempts)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the retrial was cancelled while waiting for the
+    /// next attempt.
+    pub fn is_cancelled(&self) -> bool {
+        if let RetryErrorKind::Cancelled = &self.inner {
             true
         } else {
             false
@@ -116,7 +145,8 @@ impl RetryError {
         use RetryErrorKind::*;
         let inner = match self.inner {
             Aborted(_) => unreachable!(),
-            Timeout => Timeout,
+            Timeout(attempts) => Timeout(attempts),
+            Cancelled => Cancelled,
             TimerShutdown => TimerShutdown,
         };
         RetryError { inner }
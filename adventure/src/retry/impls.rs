@@ -2,10 +2,16 @@ use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
 
-use pin_utils::unsafe_pinned;
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
 
 use super::{error::RetryError, Backoff, ExponentialBackoff, RetriableRequest, Timer};
+#[cfg(feature = "std")]
+use crate::cancel::{CancellationToken, Cancelled};
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
 use crate::oneshot::OneshotRequest;
 use crate::paginator::PagedRequest;
 use crate::request::{BaseRequest, Request};
@@ -59,6 +65,11 @@ pub struct Retrying<R, T, B = ExponentialBackoff, F = ()> {
     timer: T,
     backoff: B,
     pred: F,
+    attempt_timeout: Option<Duration>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    #[cfg(feature = "std")]
+    cancel: Option<CancellationToken>,
 }
 
 impl<R, T, B> Retrying<R, T, B>
@@ -84,6 +95,11 @@ where
             timer,
             backoff,
             pred: (),
+            attempt_timeout: None,
+            #[cfg(feature = "std")]
+            deadline: None,
+            #[cfg(feature = "std")]
+            cancel: None,
         }
     }
 
@@ -96,6 +112,11 @@ where
             timer: self.timer,
             backoff: self.backoff,
             pred,
+            attempt_timeout: self.attempt_timeout,
+            #[cfg(feature = "std")]
+            deadline: self.deadline,
+            #[cfg(feature = "std")]
+            cancel: self.cancel,
         }
     }
 }
@@ -105,6 +126,45 @@ where
     R: BaseRequest,
 {
     unsafe_pinned!(inner: R);
+
+    /// Aborts the pending wait for the next attempt with
+    /// [`RetryError::is_cancelled`], as soon as the given token is cancelled.
+    ///
+    /// An attempt in flight is not aborted by the token.
+    #[cfg(feature = "std")]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Limits the time to wait for the response of each attempt.
+    ///
+    /// An attempt which is not completed in time is given up, and retried
+    /// like failed ones, regardless of the predicate.
+    ///
+    /// As the timeout is a delay of the timer polled along with attempts,
+    /// timers blocking the thread, like [`ThreadTimer`](super::ThreadTimer)
+    /// of [`retry_blocking`](crate::util::RequestExt::retry_blocking), make
+    /// every attempt time out.
+    pub fn with_attempt_timeout(mut self, timeout: Duration) -> Self {
+        self.attempt_timeout = Some(timeout);
+        self
+    }
+
+    /// Gives up the retrial at the given instant, including attempts and
+    /// waits between them.
+    ///
+    /// If the deadline comes while waiting for the next attempt, the wait is
+    /// cut short and the retrial fails with [`RetryError::is_timeout`].
+    ///
+    /// As the deadline is a delay of the timer polled along with attempts,
+    /// timers blocking the thread, like [`ThreadTimer`](super::ThreadTimer),
+    /// make the retrial time out at its first attempt.
+    #[cfg(feature = "std")]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
 }
 
 impl<R, T, B, F> BaseRequest for Retrying<R, T, B, F>
@@ -113,13 +173,17 @@ where
 {
     type Ok = R::Ok;
     type Error = RetryError<R::Error>;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
 }
 
 impl<R, T, B, F, C> OneshotRequest<C> for Retrying<R, T, B, F>
 where
     Self: RetryMethod<C, Response = R::Response> + Unpin,
     R: Request<C>,
-    R::Response: Unpin,
     C: Clone,
 {
     type Response = Retrial<Self, C>;
@@ -130,6 +194,11 @@ where
             request: self,
             next: None,
             wait: None,
+            attempt: None,
+            deadline: None,
+            #[cfg(feature = "std")]
+            cancelled: None,
+            attempts: 0,
         }
     }
 }
@@ -138,7 +207,6 @@ impl<R, T, B, F, C> Request<C> for Retrying<R, T, B, F>
 where
     Self: RetryMethod<C, Response = R::Response> + Clone + Unpin,
     R: Request<C>,
-    R::Response: Unpin,
     C: Clone,
 {
     type Response = Retrial<Self, C>;
@@ -149,6 +217,11 @@ where
             request: self.clone(),
             next: None,
             wait: None,
+            attempt: None,
+            deadline: None,
+            #[cfg(feature = "std")]
+            cancelled: None,
+            attempts: 0,
         }
     }
 }
@@ -184,14 +257,36 @@ pub trait RetryMethod<C> {
 
     fn expires_in(&mut self, next_duration: Duration) -> Self::Delay;
 
-    fn next_wait(&mut self, err: WaitError<Self, C>) -> WaitResult<Self, C> {
-        let next = self.next_backoff().ok_or_else(RetryError::timeout)?;
+    fn attempt_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        None
+    }
+
+    #[cfg(feature = "std")]
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        None
+    }
+
+    fn next_wait(&mut self, err: WaitError<Self, C>, attempts: usize) -> WaitResult<Self, C> {
+        let next = self
+            .next_backoff()
+            .ok_or_else(|| RetryError::timeout(attempts))?;
         if self.check_retry(&err, next) {
             Ok(self.expires_in(next))
         } else {
             Err(RetryError::from_err(err))
         }
     }
+
+    fn next_wait_timed_out(&mut self, attempts: usize) -> WaitResult<Self, C> {
+        let next = self
+            .next_backoff()
+            .ok_or_else(|| RetryError::timeout(attempts))?;
+        Ok(self.expires_in(next))
+    }
 }
 
 impl<R, T, B, F, C> RetryMethod<C> for Retrying<R, T, B, F>
@@ -223,6 +318,21 @@ where
     fn expires_in(&mut self, next_duration: Duration) -> Self::Delay {
         self.timer.expires_in(next_duration)
     }
+
+    fn attempt_timeout(&self) -> Option<Duration> {
+        self.attempt_timeout
+    }
+
+    #[cfg(feature = "std")]
+    fn deadline(&self) -> Option<Duration> {
+        let deadline = self.deadline?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    #[cfg(feature = "std")]
+    fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancel.as_ref()
+    }
 }
 
 /// Response for [`retry`](crate::util::RequestExt::retry) combinator.
@@ -235,6 +345,11 @@ where
     request: R,
     next: Option<R::Response>,
     wait: Option<R::Delay>,
+    attempt: Option<R::Delay>,
+    deadline: Option<R::Delay>,
+    #[cfg(feature = "std")]
+    cancelled: Option<Cancelled>,
+    attempts: usize,
 }
 
 impl<R, C> Unpin for Retrial<R, C>
@@ -248,7 +363,6 @@ where
 impl<R, C> Future for Retrial<R, C>
 where
     R: RetryMethod<C> + Unpin,
-    R::Response: Unpin,
     C: Clone,
 {
     type Output = Result<<R::Response as Response>::Ok, RetryError<WaitError<R, C>>>;
@@ -261,17 +375,72 @@ where
 impl<R, C> Retrial<R, C>
 where
     R: RetryMethod<C> + Unpin,
-    R::Response: Unpin,
     C: Clone,
 {
     unsafe_pinned!(request: R);
     unsafe_pinned!(next: Option<R::Response>);
     unsafe_pinned!(wait: Option<R::Delay>);
+    unsafe_pinned!(attempt: Option<R::Delay>);
+    unsafe_pinned!(deadline: Option<R::Delay>);
+    #[cfg(feature = "std")]
+    unsafe_unpinned!(cancelled: Option<Cancelled>);
+    unsafe_unpinned!(attempts: usize);
+
+    #[cfg(feature = "std")]
+    fn poll_cancelled(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<()> {
+        match self.cancelled() {
+            Some(cancelled) => Pin::new(cancelled).poll(ctx),
+            None => Poll::Pending,
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn poll_cancelled(self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<()> {
+        Poll::Pending
+    }
+
+    fn set_wait(mut self: Pin<&mut Self>, wait: Option<R::Delay>) {
+        #[cfg(feature = "std")]
+        {
+            let cancelled = match wait {
+                Some(_) => self
+                    .request
+                    .cancellation()
+                    .map(CancellationToken::cancelled),
+                None => None,
+            };
+            *self.as_mut().cancelled() = cancelled;
+        }
+        self.wait().set(wait);
+    }
 
     fn poll_impl(
         mut self: Pin<&mut Self>,
         ctx: &mut Context<'_>,
     ) -> Poll<Result<<R::Response as Response>::Ok, RetryError<WaitError<R, C>>>> {
+        if self.attempts == 0 && self.deadline.is_none() {
+            if let Some(deadline) = self.request.deadline() {
+                let delay = self.as_mut().request().get_mut().expires_in(deadline);
+                self.as_mut().deadline().set(Some(delay));
+            }
+        }
+
+        if let Some(d) = self.as_mut().deadline().as_pin_mut() {
+            match d.try_poll(ctx) {
+                Poll::Pending => {}
+                Poll::Ready(Ok(())) => {
+                    return Poll::Ready(Err(RetryError::timeout(self.attempts)));
+                }
+                Poll::Ready(Err(e)) => {
+                    return Poll::Ready(Err(e.transform()));
+                }
+            }
+        }
+
+        if self.wait.is_some() && self.as_mut().poll_cancelled(ctx).is_ready() {
+            return Poll::Ready(Err(RetryError::cancelled()));
+        }
+
         if let Some(w) = self.as_mut().wait().as_pin_mut() {
             match w.try_poll(ctx) {
                 Poll::Pending => {
@@ -282,35 +451,54 @@ where
                 }
                 _ => {}
             }
-            self.as_mut().wait().set(None);
+            self.as_mut().set_wait(None);
         }
 
         if self.as_mut().next().as_pin_mut().is_none() {
             let client = self.client.clone();
-            let request = self.as_mut().request();
-            let next = request.send(client);
+            let next = self.as_mut().request().send(client);
             self.as_mut().next().set(Some(next));
+            *self.as_mut().attempts() += 1;
+
+            if let Some(timeout) = self.request.attempt_timeout() {
+                let delay = self.as_mut().request().get_mut().expires_in(timeout);
+                self.as_mut().attempt().set(Some(delay));
+            }
         }
 
-        match self
+        let result = match self
             .as_mut()
             .next()
             .as_pin_mut()
             .expect("Assertion failed")
             .try_poll(ctx)
         {
-            Poll::Pending => Poll::Pending,
-            Poll::Ready(Ok(resp)) => Poll::Ready(Ok(resp)),
-            Poll::Ready(Err(e)) => {
-                self.as_mut().next().set(None);
-                match self.as_mut().request().get_mut().next_wait(e) {
-                    Ok(w) => {
-                        self.as_mut().wait().set(Some(w));
-                        self.poll_impl(ctx)
-                    }
-                    Err(e) => Poll::Ready(Err(e)),
-                }
+            Poll::Ready(Ok(resp)) => return Poll::Ready(Ok(resp)),
+            Poll::Ready(Err(e)) => Some(e),
+            Poll::Pending => match self.as_mut().attempt().as_pin_mut() {
+                Some(a) => match a.try_poll(ctx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(())) => None,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e.transform())),
+                },
+                None => return Poll::Pending,
+            },
+        };
+
+        self.as_mut().next().set(None);
+        self.as_mut().attempt().set(None);
+        let attempts = self.attempts;
+        let request = self.as_mut().request().get_mut();
+        let wait = match result {
+            Some(e) => request.next_wait(e, attempts),
+            None => request.next_wait_timed_out(attempts),
+        };
+        match wait {
+            Ok(w) => {
+                self.as_mut().set_wait(Some(w));
+                self.poll_impl(ctx)
             }
+            Err(e) => Poll::Ready(Err(e)),
         }
     }
 }
@@ -1,4 +1,6 @@
 pub mod backoff;
+#[cfg(feature = "std")]
+pub mod thread;
 #[cfg(feature = "tokio-timer")]
 pub mod tokio;
 
@@ -12,6 +14,9 @@ use core::time::Duration;
 use crate::request::BaseRequest;
 use crate::response::Response;
 
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use self::thread::ThreadTimer;
 #[cfg(feature = "tokio-timer")]
 #[doc(inline)]
 pub use self::tokio::TokioTimer;
@@ -21,37 +26,14 @@ pub use self::{
     impls::{Retrial, RetrialPredicate, Retrying},
 };
 
+#[cfg(feature = "std")]
+pub type RetryingThread<R, B = ExponentialBackoff, F = ()> = Retrying<R, ThreadTimer, B, F>;
 #[cfg(feature = "tokio-timer")]
 pub type RetryingTokio<R, B = ExponentialBackoff, F = ()> = Retrying<R, TokioTimer, B, F>;
 
 /// A request able to decide to send itself again if the previous attempt has failed.
 pub trait RetriableRequest: BaseRequest {
     fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool;
-
-    /// Wrap this request to retry itself on failure, with a default [`ExponentialBackoff`] strategy.
-    ///
-    /// It should be called within the tokio execution context,
-    /// because the default timer is implemented using [`tokio_timer`].
-    #[cfg(feature = "tokio-timer")]
-    fn retry(self) -> RetryingTokio<Self>
-    where
-        Self: Sized,
-    {
-        RetryingTokio::from_default(self)
-    }
-
-    /// Wrap this request to retry itself on failure, with a given backoff strategy.
-    ///
-    /// It should be called within the tokio execution context,
-    /// because the default timer is implemented using [`tokio_timer`].
-    #[cfg(feature = "tokio-timer")]
-    fn retry_with_backoff<B>(self, backoff: B) -> RetryingTokio<Self, B>
-    where
-        Self: BaseRequest + Sized,
-        B: Backoff,
-    {
-        RetryingTokio::new(self, Default::default(), backoff)
-    }
 }
 
 impl<R> RetriableRequest for &R
@@ -81,7 +63,7 @@ pub trait Timer {
 
 #[cfg(feature = "alloc")]
 mod feature_alloc {
-    use alloc::boxed::Box;
+    use alloc::{boxed::Box, rc::Rc, sync::Arc};
 
     use super::*;
 
@@ -93,4 +75,22 @@ mod feature_alloc {
             (**self).should_retry(error, next_interval)
         }
     }
+
+    impl<R> RetriableRequest for Rc<R>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            (**self).should_retry(error, next_interval)
+        }
+    }
+
+    impl<R> RetriableRequest for Arc<R>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            (**self).should_retry(error, next_interval)
+        }
+    }
 }
@@ -0,0 +1,52 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{RetryError, Timer};
+
+/// Provides a delayed response by putting the current thread to sleep,
+/// without any async runtime.
+///
+/// Because its delays block the thread while being polled, it should be used
+/// with blocking executors, like `send_blocking` of `blocking` feature.
+///
+/// For the same reason, it can't limit the time to wait for responses: a
+/// delay polled along with a response, by
+/// [`timeout_with_timer`](crate::util::RequestExt::timeout_with_timer),
+/// [`with_attempt_timeout`](super::Retrying::with_attempt_timeout) or
+/// [`with_deadline`](super::Retrying::with_deadline), sleeps until it
+/// expires, so the response always times out. Use it only to wait between
+/// attempts.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadTimer;
+
+impl Timer for ThreadTimer {
+    type Delay = ThreadDelay;
+
+    fn expires_in(&mut self, interval: Duration) -> Self::Delay {
+        ThreadDelay {
+            deadline: Instant::now() + interval,
+        }
+    }
+}
+
+/// A delayed response from [`ThreadTimer`].
+#[must_use = "responses do nothing unless polled"]
+#[derive(Debug)]
+pub struct ThreadDelay {
+    deadline: Instant,
+}
+
+impl Future for ThreadDelay {
+    type Output = Result<(), RetryError>;
+
+    fn poll(self: Pin<&mut Self>, _ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        let now = Instant::now();
+        if now < self.deadline {
+            thread::sleep(self.deadline - now);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
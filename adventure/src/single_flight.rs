@@ -0,0 +1,366 @@
+//! Coalescing identical requests in flight into a single response.
+use core::future::Future;
+use core::hash::Hash;
+use core::mem;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use futures::channel::oneshot;
+use futures::future::{FutureExt, IntoFuture, Shared, TryFutureExt};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+use crate::cache::RequestKey;
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+
+/// A group of requests in flight, to share a response with requests of the
+/// same key sent while it is not completed.
+///
+/// The shared response is driven by any of requests waiting for it, so the
+/// others are not affected even if the first one has been dropped.
+///
+/// Only the first request of a key is sent, to the client given with it.
+/// The other requests receive its response, even if they have been given
+/// different clients.
+pub struct SingleFlight<K, F>
+where
+    F: Response,
+{
+    inner: Mutex<Flights<K, F>>,
+}
+
+struct Flights<K, F>
+where
+    F: Response,
+{
+    next_id: u64,
+    in_flight: HashMap<K, (u64, Flight<F>)>,
+}
+
+type SharedResponse<F> = Shared<IntoFuture<F>>;
+
+enum Flight<F>
+where
+    F: Response,
+{
+    /// The first request is being sent, without holding the lock of the
+    /// group.
+    Sending(Shared<oneshot::Receiver<SharedResponse<F>>>),
+    Sent(SharedResponse<F>),
+}
+
+impl<F> Clone for Flight<F>
+where
+    F: Response,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Flight::Sending(receiver) => Flight::Sending(receiver.clone()),
+            Flight::Sent(response) => Flight::Sent(response.clone()),
+        }
+    }
+}
+
+impl<K, F> Default for SingleFlight<K, F>
+where
+    K: Hash + Eq,
+    F: Response,
+{
+    fn default() -> Self {
+        SingleFlight {
+            inner: Mutex::new(Flights {
+                next_id: 0,
+                in_flight: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl<K, F> SingleFlight<K, F>
+where
+    K: Hash + Eq,
+    F: Response,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the number of responses in flight.
+    pub fn len(&self) -> usize {
+        self.lock().in_flight.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Flights<K, F>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn join<S>(&self, key: &K, send: S) -> (u64, Flight<F>)
+    where
+        K: Clone,
+        F::Ok: Clone,
+        F::Error: Clone,
+        S: FnOnce() -> F,
+    {
+        let (id, sender) = {
+            let mut flights = self.lock();
+            if let Some((id, flight)) = flights.in_flight.get(key) {
+                return (*id, flight.clone());
+            }
+            let id = flights.next_id;
+            flights.next_id += 1;
+            let (sender, receiver) = oneshot::channel();
+            let flight = Flight::Sending(receiver.shared());
+            flights.in_flight.insert(key.clone(), (id, flight));
+            (id, sender)
+        };
+
+        // The request is sent without the lock, as it may send other
+        // requests of this group.
+        let guard = SendGuard {
+            group: self,
+            key,
+            id,
+        };
+        let response = send().into_future().shared();
+        mem::forget(guard);
+
+        if let Some((current, flight)) = self.lock().in_flight.get_mut(key) {
+            if *current == id {
+                *flight = Flight::Sent(response.clone());
+            }
+        }
+        let _ = sender.send(response.clone());
+        (id, Flight::Sent(response))
+    }
+
+    fn complete(&self, key: &K, id: u64) {
+        let mut flights = self.lock();
+        let current = match flights.in_flight.get(key) {
+            Some((current, _)) => *current,
+            None => return,
+        };
+        if current == id {
+            flights.in_flight.remove(key);
+        }
+    }
+}
+
+/// Forgets the request being sent, if sending it has panicked.
+struct SendGuard<'a, K, F>
+where
+    K: Hash + Eq,
+    F: Response,
+{
+    group: &'a SingleFlight<K, F>,
+    key: &'a K,
+    id: u64,
+}
+
+impl<K, F> Drop for SendGuard<'_, K, F>
+where
+    K: Hash + Eq,
+    F: Response,
+{
+    fn drop(&mut self) {
+        self.group.complete(self.key, self.id);
+    }
+}
+
+/// Request for [`coalesce`](crate::util::RequestExt::coalesce) combinator.
+///
+/// See [`SingleFlight`] for which client the request is sent to.
+#[derive(Clone)]
+pub struct Coalesced<R, G> {
+    inner: R,
+    group: G,
+}
+
+impl<R, G> Coalesced<R, G> {
+    unsafe_pinned!(inner: R);
+
+    pub(crate) fn new(req: R, group: G) -> Self {
+        Coalesced { inner: req, group }
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, G> BaseRequest for Coalesced<R, G>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, G, C> OneshotRequest<C> for Coalesced<R, G>
+where
+    R: OneshotRequest<C> + RequestKey,
+    R::Ok: Clone,
+    R::Error: Clone,
+    R::Key: Hash + Eq + Clone,
+    G: Deref<Target = SingleFlight<R::Key, R::Response>>,
+{
+    type Response = CoalescedResponse<R::Key, R::Response, G>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let key = self.inner.key();
+        let inner = self.inner;
+        let (id, flight) = self.group.join(&key, move || inner.send_once(client));
+        CoalescedResponse::new(flight, key, id, self.group)
+    }
+}
+
+impl<R, G, C> Request<C> for Coalesced<R, G>
+where
+    R: Request<C> + RequestKey,
+    R::Ok: Clone,
+    R::Error: Clone,
+    R::Key: Hash + Eq + Clone,
+    G: Deref<Target = SingleFlight<R::Key, R::Response>> + Clone,
+{
+    type Response = CoalescedResponse<R::Key, R::Response, G>;
+
+    fn send(self: Pin<&mut Self>, client: C) -> Self::Response {
+        let key = self.inner.key();
+        let group = self.group.clone();
+        let inner = self.inner();
+        let (id, flight) = group.join(&key, move || inner.send(client));
+        CoalescedResponse::new(flight, key, id, group)
+    }
+}
+
+impl<R, G> Unpin for Coalesced<R, G> where R: Unpin {}
+
+/// Response for [`coalesce`](crate::util::RequestExt::coalesce) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct CoalescedResponse<K, F, G>
+where
+    F: Response,
+{
+    flight: Flight<F>,
+    key: K,
+    id: u64,
+    group: G,
+}
+
+impl<K, F, G> CoalescedResponse<K, F, G>
+where
+    F: Response,
+{
+    unsafe_unpinned!(flight: Flight<F>);
+
+    fn new(flight: Flight<F>, key: K, id: u64, group: G) -> Self {
+        CoalescedResponse {
+            flight,
+            key,
+            id,
+            group,
+        }
+    }
+}
+
+impl<K, F, G> Unpin for CoalescedResponse<K, F, G> where F: Response {}
+
+impl<K, F, G> Future for CoalescedResponse<K, F, G>
+where
+    K: Hash + Eq,
+    F: Response,
+    F::Ok: Clone,
+    F::Error: Clone,
+    G: Deref<Target = SingleFlight<K, F>>,
+{
+    type Output = Result<F::Ok, F::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let response = match self.as_mut().flight() {
+                Flight::Sending(receiver) => match receiver.poll_unpin(ctx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(response) => {
+                        response.expect("the first request has panicked while sent")
+                    }
+                },
+                Flight::Sent(response) => match response.poll_unpin(ctx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(result) => {
+                        self.group.complete(&self.key, self.id);
+                        return Poll::Ready(result);
+                    }
+                },
+            };
+            *self.as_mut().flight() = Flight::Sent(response);
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::Coalesced;
+    use crate::retry::RetriableRequest;
+
+    impl<R, G> RetriableRequest for Coalesced<R, G>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::Coalesced;
+    use crate::paginator::PagedRequest;
+
+    impl<R, G> PagedRequest for Coalesced<R, G>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_hedge {
+    use super::Coalesced;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, G> IdempotentRequest for Coalesced<R, G> where R: IdempotentRequest {}
+}
+
+mod impl_cache {
+    use super::Coalesced;
+    use crate::cache::RequestKey;
+
+    impl<R, G> RequestKey for Coalesced<R, G>
+    where
+        R: RequestKey,
+    {
+        type Key = R::Key;
+
+        fn key(&self) -> Self::Key {
+            self.inner.key()
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! An adaptor to limit the time to wait for responses.
+use core::fmt::{self, Display};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+use crate::response::Response;
+use crate::retry::{RetryError, Timer};
+
+#[cfg(feature = "tokio-timer")]
+use crate::retry::TokioTimer;
+
+#[cfg(feature = "tokio-timer")]
+pub type TimeoutTokio<R> = Timeout<R, TokioTimer>;
+
+/// Request for [`timeout`](crate::util::RequestExt::timeout) combinator.
+///
+/// The delay of the timer is polled along with the response, so timers
+/// blocking the thread, like [`ThreadTimer`](crate::retry::ThreadTimer), make
+/// it always time out.
+#[derive(Clone)]
+pub struct Timeout<R, T> {
+    inner: R,
+    timer: T,
+    duration: Duration,
+}
+
+impl<R, T> Timeout<R, T> {
+    unsafe_pinned!(inner: R);
+    unsafe_unpinned!(timer: T);
+
+    pub(crate) fn new(req: R, timer: T, duration: Duration) -> Self {
+        Timeout {
+            inner: req,
+            timer,
+            duration,
+        }
+    }
+
+    /// Returns the time limit of each response.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, T> BaseRequest for Timeout<R, T>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = TimeoutError<R::Error>;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, T, C> OneshotRequest<C> for Timeout<R, T>
+where
+    R: OneshotRequest<C>,
+    T: Timer,
+{
+    type Response = TimeoutResponse<R::Response, T::Delay>;
+
+    fn send_once(mut self, client: C) -> Self::Response {
+        let delay = self.timer.expires_in(self.duration);
+        TimeoutResponse::new(self.inner.send_once(client), delay)
+    }
+}
+
+impl<R, T, C> Request<C> for Timeout<R, T>
+where
+    R: Request<C>,
+    T: Timer,
+{
+    type Response = TimeoutResponse<R::Response, T::Delay>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let duration = self.duration;
+        let delay = self.as_mut().timer().expires_in(duration);
+        TimeoutResponse::new(self.inner().send(client), delay)
+    }
+}
+
+impl<R, T> Unpin for Timeout<R, T>
+where
+    R: Unpin,
+    T: Unpin,
+{
+}
+
+/// Response for [`timeout`](crate::util::RequestExt::timeout) combinator.
+#[must_use = "responses do nothing unless polled"]
+pub struct TimeoutResponse<F, D> {
+    inner: F,
+    delay: D,
+}
+
+impl<F, D> TimeoutResponse<F, D> {
+    unsafe_pinned!(inner: F);
+    unsafe_pinned!(delay: D);
+
+    fn new(inner: F, delay: D) -> Self {
+        TimeoutResponse { inner, delay }
+    }
+}
+
+impl<F, D> Unpin for TimeoutResponse<F, D>
+where
+    F: Unpin,
+    D: Unpin,
+{
+}
+
+impl<F, D> Future for TimeoutResponse<F, D>
+where
+    F: Response,
+    D: Response<Ok = (), Error = RetryError>,
+{
+    type Output = Result<F::Ok, TimeoutError<F::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(r) = self.as_mut().inner().try_poll(ctx) {
+            return Poll::Ready(r.map_err(TimeoutError::from_err));
+        }
+
+        match self.delay().try_poll(ctx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(())) => Poll::Ready(Err(TimeoutError::elapsed())),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(TimeoutError::shutdown())),
+        }
+    }
+}
+
+/// Errors encountered by the request with a time limit.
+#[derive(Debug)]
+pub struct TimeoutError<E> {
+    inner: TimeoutErrorKind<E>,
+}
+
+#[derive(Debug)]
+enum TimeoutErrorKind<E> {
+    Inner(E),
+    Elapsed,
+    TimerShutdown,
+}
+
+impl<E: Display> Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TimeoutErrorKind::*;
+        match &self.inner {
+            Inner(e) => e.fmt(f),
+            Elapsed => "Deadline has elapsed".fmt(f),
+            TimerShutdown => "Timer has gone".fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: StdError + 'static> StdError for TimeoutError<E> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.inner {
+            TimeoutErrorKind::Inner(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E> TimeoutError<E> {
+    pub fn from_err(e: E) -> Self {
+        TimeoutError {
+            inner: TimeoutErrorKind::Inner(e),
+        }
+    }
+
+    pub(crate) const fn elapsed() -> Self {
+        TimeoutError {
+            inner: TimeoutErrorKind::Elapsed,
+        }
+    }
+
+    pub(crate) const fn shutdown() -> Self {
+        TimeoutError {
+            inner: TimeoutErrorKind::TimerShutdown,
+        }
+    }
+
+    pub fn as_inner(&self) -> Option<&E> {
+        if let TimeoutErrorKind::Inner(e) = &self.inner {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    pub fn into_inner(self) -> Option<E> {
+        if let TimeoutErrorKind::Inner(e) = self.inner {
+            Some(e)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the error was caused by the inner request.
+    pub fn is_inner(&self) -> bool {
+        self.as_inner().is_some()
+    }
+
+    /// Returns `true` if the error was caused by the time limit has elapsed.
+    pub fn is_elapsed(&self) -> bool {
+        match &self.inner {
+            TimeoutErrorKind::Elapsed => true,
+            TimeoutErrorKind::Inner(_) | TimeoutErrorKind::TimerShutdown => false,
+        }
+    }
+
+    /// Returns `true` if the error was caused by the timer begin shutdown.
+    pub fn is_shutdown(&self) -> bool {
+        match &self.inner {
+            TimeoutErrorKind::TimerShutdown => true,
+            TimeoutErrorKind::Inner(_) | TimeoutErrorKind::Elapsed => false,
+        }
+    }
+}
+
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::{Timeout, TimeoutErrorKind};
+    use crate::retry::RetriableRequest;
+
+    // a timed out attempt is always considered to be retried.
+    impl<R, T> RetriableRequest for Timeout<R, T>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            match &error.inner {
+                TimeoutErrorKind::Inner(e) => self.inner.should_retry(e, next_interval),
+                TimeoutErrorKind::Elapsed => true,
+                TimeoutErrorKind::TimerShutdown => false,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod impl_hedge {
+    use super::Timeout;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R, T> IdempotentRequest for Timeout<R, T> where R: IdempotentRequest {}
+}
+
+mod impl_paginator {
+    use super::Timeout;
+    use crate::paginator::PagedRequest;
+
+    impl<R, T> PagedRequest for Timeout<R, T>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Interoperability with [`tower`](https://docs.rs/tower) services.
+//!
+//! [`ClientService`] makes a client usable as a [`Service`] for any
+//! [`OneshotRequest`] to it, so it can be wrapped by middlewares from the
+//! ecosystem of `tower`. On the other hand, [`ServiceRequest`] sends a request
+//! through an arbitrary [`Service`], which can be
+//! [`paginate`](crate::util::RequestExt::paginate)d or
+//! [`retry`](crate::util::RequestExt::retry)ed like the other ones.
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures::future::{IntoFuture, TryFutureExt};
+use futures::ready;
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+use tower_service::Service;
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+
+/// A [`Service`] which sends requests to the underlying client.
+///
+/// It is always ready to accept requests, and each request is sent to a clone
+/// of the client.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClientService<C> {
+    client: C,
+}
+
+impl<C> ClientService<C> {
+    pub fn new(client: C) -> Self {
+        ClientService { client }
+    }
+
+    /// Returns a reference to the underlying client.
+    pub fn get_ref(&self) -> &C {
+        &self.client
+    }
+
+    /// Consumes this service, returning the underlying client.
+    pub fn into_inner(self) -> C {
+        self.client
+    }
+}
+
+impl<C, R> Service<R> for ClientService<C>
+where
+    R: OneshotRequest<C>,
+    C: Clone,
+{
+    type Response = R::Ok;
+    type Error = R::Error;
+    type Future = IntoFuture<R::Response>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        req.send_once(self.client.clone()).into_future()
+    }
+}
+
+/// A request which is sent through a [`Service`] accepting the underlying
+/// request, instead of a client.
+///
+/// The service should respond with the same types of results as the
+/// underlying request. A mutable reference to the service can be given to
+/// send it once, but paginating or retrying it needs a service which can be
+/// cloned, like a clone of the service, because `&mut S` is not `Clone`.
+#[derive(Clone, Debug)]
+pub struct ServiceRequest<R> {
+    inner: R,
+}
+
+impl<R> ServiceRequest<R> {
+    pub fn new(req: R) -> Self {
+        ServiceRequest { inner: req }
+    }
+
+    /// Consumes this adaptor, returning the underlying request.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> From<R> for ServiceRequest<R> {
+    fn from(req: R) -> Self {
+        ServiceRequest::new(req)
+    }
+}
+
+impl<R> BaseRequest for ServiceRequest<R>
+where
+    R: BaseRequest,
+{
+    type Ok = R::Ok;
+    type Error = R::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.inner.extensions()
+    }
+}
+
+impl<R, S> OneshotRequest<S> for ServiceRequest<R>
+where
+    R: BaseRequest,
+    S: Service<R, Response = R::Ok, Error = R::Error>,
+{
+    type Response = ServiceResponse<S, R>;
+
+    fn send_once(self, service: S) -> Self::Response {
+        ServiceResponse::new(service, self.inner)
+    }
+}
+
+impl<R, S> Request<S> for ServiceRequest<R>
+where
+    R: BaseRequest + Clone,
+    S: Service<R, Response = R::Ok, Error = R::Error>,
+{
+    type Response = ServiceResponse<S, R>;
+
+    fn send(self: Pin<&mut Self>, service: S) -> Self::Response {
+        ServiceResponse::new(service, self.inner.clone())
+    }
+}
+
+impl<R> Unpin for ServiceRequest<R> where R: Unpin {}
+
+/// Response for [`ServiceRequest`], which waits for the service to be ready
+/// before calling it.
+#[must_use = "responses do nothing unless polled"]
+pub struct ServiceResponse<S, R>
+where
+    S: Service<R>,
+{
+    service: S,
+    request: Option<R>,
+    future: Option<S::Future>,
+}
+
+impl<S, R> ServiceResponse<S, R>
+where
+    S: Service<R>,
+{
+    unsafe_unpinned!(service: S);
+    unsafe_unpinned!(request: Option<R>);
+    unsafe_pinned!(future: Option<S::Future>);
+
+    fn new(service: S, request: R) -> Self {
+        ServiceResponse {
+            service,
+            request: Some(request),
+            future: None,
+        }
+    }
+}
+
+impl<S, R> Unpin for ServiceResponse<S, R>
+where
+    S: Service<R>,
+    S::Future: Unpin,
+{
+}
+
+impl<S, R> Future for ServiceResponse<S, R>
+where
+    S: Service<R>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.request.is_some() {
+            if let Err(e) = ready!(self.as_mut().service().poll_ready(cx)) {
+                return Poll::Ready(Err(e));
+            }
+            let request = self
+                .as_mut()
+                .request()
+                .take()
+                .expect("the request has been checked above");
+            let future = self.as_mut().service().call(request);
+            self.as_mut().future().set(Some(future));
+        }
+        self.future()
+            .as_pin_mut()
+            .expect("the request has been called")
+            .poll(cx)
+    }
+}
+
+#[cfg(feature = "backoff")]
+mod impl_retry {
+    use core::time::Duration;
+
+    use super::ServiceRequest;
+    use crate::retry::RetriableRequest;
+
+    impl<R> RetriableRequest for ServiceRequest<R>
+    where
+        R: RetriableRequest,
+    {
+        fn should_retry(&self, error: &Self::Error, next_interval: Duration) -> bool {
+            self.inner.should_retry(error, next_interval)
+        }
+    }
+}
+
+mod impl_paginator {
+    use super::ServiceRequest;
+    use crate::paginator::PagedRequest;
+
+    impl<R> PagedRequest for ServiceRequest<R>
+    where
+        R: PagedRequest,
+    {
+        fn advance(&mut self, response: &Self::Ok) -> bool {
+            self.inner.advance(response)
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+mod impl_hedge {
+    use super::ServiceRequest;
+    use crate::hedge::IdempotentRequest;
+
+    impl<R> IdempotentRequest for ServiceRequest<R> where R: IdempotentRequest {}
+}
+
+#[cfg(feature = "std")]
+mod impl_cache {
+    use super::ServiceRequest;
+    use crate::cache::RequestKey;
+
+    impl<R> RequestKey for ServiceRequest<R>
+    where
+        R: RequestKey,
+    {
+        type Key = R::Key;
+
+        fn key(&self) -> Self::Key {
+            self.inner.key()
+        }
+    }
+}
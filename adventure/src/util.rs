@@ -0,0 +1,394 @@
+//! Extension traits to provide the combinators of requests.
+//!
+//! These traits are implemented for every type of requests, including
+//! references, pinned pointers, and boxes of them. Other crates can provide
+//! their own combinators in the same way, without changing the request
+//! traits.
+#[cfg(feature = "std")]
+use core::ops::Deref;
+#[cfg(any(feature = "std", feature = "backoff"))]
+use core::time::Duration;
+
+#[cfg(feature = "blocking")]
+use futures::future::TryFutureExt;
+
+use crate::and_then::AndThen;
+#[cfg(feature = "blocking")]
+use crate::blocking::BlockingPaginator;
+#[cfg(feature = "std")]
+use crate::breaker::{CircuitBreaker, Clock, WithBreaker};
+#[cfg(feature = "std")]
+use crate::cache::{CacheStore, Cached, RequestKey};
+#[cfg(feature = "alloc")]
+use crate::extensions::WithExtensions;
+use crate::layer::WithMiddleware;
+use crate::map::{MapErr, MapOk};
+use crate::oneshot::{Oneshot, OneshotRequest};
+use crate::or_else::OrElse;
+use crate::paginator::{PagedRequest, Paginator};
+#[cfg(feature = "std")]
+use crate::pool::Pooled;
+use crate::race::{self, Race};
+use crate::repeat::Repeat;
+use crate::request::{BaseRequest, Request};
+#[cfg(feature = "std")]
+use crate::single_flight::Coalesced;
+use crate::zip::{self, Zip};
+
+#[cfg(all(feature = "alloc", feature = "backoff", feature = "tokio-timer"))]
+use crate::hedge::HedgeTokio;
+#[cfg(all(feature = "alloc", feature = "backoff"))]
+use crate::hedge::{Hedge, IdempotentRequest};
+#[cfg(all(feature = "std", feature = "backoff", feature = "tokio-timer"))]
+use crate::rate_limit::RateLimitedTokio;
+#[cfg(all(feature = "std", feature = "backoff"))]
+use crate::rate_limit::{RateLimited, RateLimiter};
+#[cfg(all(
+    feature = "backoff",
+    any(feature = "tokio-timer", feature = "blocking")
+))]
+use crate::retry::RetriableRequest;
+#[cfg(feature = "blocking")]
+use crate::retry::RetryingThread;
+#[cfg(feature = "backoff")]
+use crate::retry::{Backoff, RetrialPredicate, Retrying, Timer};
+#[cfg(feature = "backoff")]
+use crate::timeout::Timeout;
+#[cfg(all(feature = "backoff", feature = "tokio-timer"))]
+use crate::{
+    retry::{ExponentialBackoff, RetryingTokio, TokioTimer},
+    timeout::TimeoutTokio,
+};
+
+/// An extension trait for [`BaseRequest`]s, which provides the combinators
+/// of requests.
+pub trait RequestExt: BaseRequest {
+    /// Wrap this request to transform the successful value of its responses.
+    fn map_ok<F, T>(self, f: F) -> MapOk<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Ok) -> T,
+    {
+        MapOk::new(self, f)
+    }
+
+    /// Wrap this request to transform the failure of its responses.
+    fn map_err<F, E>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Error) -> E,
+    {
+        MapErr::new(self, f)
+    }
+
+    /// Wrap this request to send another request built from its successful
+    /// value, to the same client.
+    fn and_then<F, R>(self, f: F) -> AndThen<Self, F, R>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Ok) -> R,
+        R: BaseRequest,
+    {
+        AndThen::new(self, f)
+    }
+
+    /// Wrap this request to send an alternative request built from its
+    /// failure, to the same client.
+    fn or_else<F, R>(self, f: F) -> OrElse<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Error) -> Result<R, R::Error>,
+        R: BaseRequest<Ok = Self::Ok>,
+    {
+        OrElse::new(self, f)
+    }
+
+    /// Wrap this request to be sent along with the given request
+    /// concurrently, resulting in a pair of their successful values.
+    ///
+    /// See [`zip`](crate::zip::zip) for details.
+    fn zip<R>(self, other: R) -> Zip<Self, R>
+    where
+        Self: Sized,
+        R: BaseRequest<Error = Self::Error>,
+    {
+        zip::zip(self, other)
+    }
+
+    /// Wrap this request to be sent along with the given request
+    /// concurrently, resulting in the first successful value of them.
+    ///
+    /// See [`race`](crate::race::race) for details.
+    fn race<R>(self, other: R) -> Race<Self, R>
+    where
+        Self: Sized,
+        R: BaseRequest<Ok = Self::Ok, Error = Self::Error>,
+    {
+        race::race(self, other)
+    }
+
+    /// Wrap this request to be sent through the given middleware.
+    fn with_middleware<M>(self, middleware: M) -> WithMiddleware<Self, M>
+    where
+        Self: Sized,
+    {
+        WithMiddleware::new(self, middleware)
+    }
+
+    /// Wrap this request to attach the given value to its extensions.
+    #[cfg(feature = "alloc")]
+    fn with_extension<T>(self, value: T) -> WithExtensions<Self>
+    where
+        Self: Sized,
+        T: Send + Sync + 'static,
+    {
+        WithExtensions::new(self).with_extension(value)
+    }
+
+    /// Wrap this request to be sent just once, by consuming itself.
+    fn oneshot(self) -> Oneshot<Self>
+    where
+        Self: Sized,
+    {
+        Oneshot::from(self)
+    }
+
+    /// Returns a stream over the pages retrieved by sending this request
+    /// and its subsequent ones to the given client.
+    fn paginate<C>(self, client: C) -> Paginator<C, Self>
+    where
+        Self: PagedRequest + Request<C> + Sized,
+    {
+        Paginator::new(client, self)
+    }
+
+    /// Returns an iterator over the pages, which blocks the current thread
+    /// until each page is retrieved.
+    #[cfg(feature = "blocking")]
+    fn paginate_blocking<C>(self, client: C) -> BlockingPaginator<C, Self>
+    where
+        Self: PagedRequest + Request<C> + Sized,
+    {
+        BlockingPaginator::from(Paginator::new(client, self))
+    }
+
+    /// Wrap this request to retry itself on failure, with a default [`ExponentialBackoff`] strategy.
+    ///
+    /// It should be called within the tokio execution context,
+    /// because the default timer is implemented using [`tokio_timer`].
+    #[cfg(all(feature = "backoff", feature = "tokio-timer"))]
+    fn retry(self) -> RetryingTokio<Self>
+    where
+        Self: RetriableRequest + Sized,
+    {
+        RetryingTokio::from_default(self)
+    }
+
+    /// Wrap this request to retry itself on failure, with a given backoff strategy.
+    ///
+    /// It should be called within the tokio execution context,
+    /// because the default timer is implemented using [`tokio_timer`].
+    #[cfg(all(feature = "backoff", feature = "tokio-timer"))]
+    fn retry_with_backoff<B>(self, backoff: B) -> RetryingTokio<Self, B>
+    where
+        Self: RetriableRequest + Sized,
+        B: Backoff,
+    {
+        RetryingTokio::new(self, Default::default(), backoff)
+    }
+
+    /// Wrap this request to retry itself on failure, with a default [`ExponentialBackoff`] strategy,
+    /// by blocking the current thread between attempts.
+    ///
+    /// It doesn't need any async runtime, but should be sent with blocking executors like
+    /// [`send_blocking`](OneshotRequestExt::send_blocking).
+    #[cfg(feature = "blocking")]
+    fn retry_blocking(self) -> RetryingThread<Self>
+    where
+        Self: RetriableRequest + Sized,
+    {
+        RetryingThread::from_default(self)
+    }
+
+    /// Wrap this request to retry if the given predicate returns `true`.
+    ///
+    /// It should be called within the tokio execution context,
+    /// because the default timer is implemented using [`tokio_timer`].
+    #[cfg(all(feature = "backoff", feature = "tokio-timer"))]
+    fn retry_if<F>(self, pred: F) -> RetryingTokio<Self, ExponentialBackoff, F>
+    where
+        Self: Sized,
+        F: RetrialPredicate<Self>,
+    {
+        RetryingTokio::from_default(self).with_predicate(pred)
+    }
+
+    /// Wrap this request to retry with customizable options, including the timer implementation.
+    #[cfg(feature = "backoff")]
+    fn retry_with_config<T, B, F>(self, timer: T, pred: F, backoff: B) -> Retrying<Self, T, B, F>
+    where
+        Self: Sized,
+        T: Timer + Unpin,
+        B: Backoff,
+        F: RetrialPredicate<Self>,
+    {
+        Retrying::new(self, timer, backoff).with_predicate(pred)
+    }
+
+    /// Wrap this request to fail if its response is not completed within
+    /// the given duration.
+    ///
+    /// It should be called within the tokio execution context,
+    /// because the default timer is implemented using [`tokio_timer`].
+    #[cfg(all(feature = "backoff", feature = "tokio-timer"))]
+    fn timeout(self, duration: Duration) -> TimeoutTokio<Self>
+    where
+        Self: Sized,
+    {
+        Timeout::new(self, TokioTimer, duration)
+    }
+
+    /// Wrap this request to fail if its response is not completed within
+    /// the given duration, using the given timer implementation.
+    ///
+    /// The timer must not block the thread while its delay is polled, as
+    /// [`ThreadTimer`](crate::retry::ThreadTimer) does; otherwise the response
+    /// always times out.
+    #[cfg(feature = "backoff")]
+    fn timeout_with_timer<T>(self, timer: T, duration: Duration) -> Timeout<Self, T>
+    where
+        Self: Sized,
+        T: Timer,
+    {
+        Timeout::new(self, timer, duration)
+    }
+
+    /// Wrap this request to send another copy of itself if the response is
+    /// not completed within the given delay.
+    ///
+    /// It should be called within the tokio execution context,
+    /// because the default timer is implemented using [`tokio_timer`].
+    #[cfg(all(feature = "alloc", feature = "backoff", feature = "tokio-timer"))]
+    fn hedge(self, delay: Duration) -> HedgeTokio<Self>
+    where
+        Self: IdempotentRequest + Sized,
+    {
+        Hedge::new(self, TokioTimer, delay)
+    }
+
+    /// Wrap this request to send another copy of itself if the response is
+    /// not completed within the given delay, using the given timer
+    /// implementation.
+    #[cfg(all(feature = "alloc", feature = "backoff"))]
+    fn hedge_with_timer<T>(self, timer: T, delay: Duration) -> Hedge<Self, T>
+    where
+        Self: IdempotentRequest + Sized,
+        T: Timer,
+    {
+        Hedge::new(self, timer, delay)
+    }
+
+    /// Wrap this request to reuse its successful responses cached in the
+    /// given store, for the given duration.
+    #[cfg(feature = "std")]
+    fn cached<S>(self, store: S, ttl: Duration) -> Cached<Self, S>
+    where
+        Self: RequestKey + Sized,
+        Self::Ok: Clone,
+        S: CacheStore<Self::Key, Result<Self::Ok, Self::Error>>,
+    {
+        Cached::new(self, store, ttl)
+    }
+
+    /// Wrap this request to share the response with other requests of the
+    /// same key, while it is in flight in the given group.
+    #[cfg(feature = "std")]
+    fn coalesce<G>(self, group: G) -> Coalesced<Self, G>
+    where
+        Self: RequestKey + Sized,
+    {
+        Coalesced::new(self, group)
+    }
+
+    /// Wrap this request to fail immediately without being sent while the
+    /// given circuit breaker is open, and to record its responses to the
+    /// breaker.
+    #[cfg(feature = "std")]
+    fn with_breaker<B, K>(self, breaker: B) -> WithBreaker<Self, B>
+    where
+        Self: Sized,
+        B: Deref<Target = CircuitBreaker<K>>,
+        K: Clock,
+    {
+        WithBreaker::new(self, breaker)
+    }
+
+    /// Wrap this request to be sent to one of clients in a
+    /// [`ClientPool`](crate::pool::ClientPool), which is given as the client
+    /// instead.
+    ///
+    /// A client is picked for every attempt, so each page of a paginator or
+    /// each retrial can be sent to a different client.
+    #[cfg(feature = "std")]
+    fn pooled(self) -> Pooled<Self>
+    where
+        Self: Sized,
+    {
+        Pooled::new(self)
+    }
+
+    /// Wrap this request to wait for a permit from the given limiter before
+    /// sending it.
+    ///
+    /// It should be called within the tokio execution context,
+    /// because the default timer is implemented using [`tokio_timer`].
+    #[cfg(all(feature = "std", feature = "backoff", feature = "tokio-timer"))]
+    fn rate_limit<L>(self, limiter: L) -> RateLimitedTokio<Self, L>
+    where
+        Self: Sized,
+        L: RateLimiter,
+    {
+        RateLimited::new(self, limiter, TokioTimer)
+    }
+
+    /// Wrap this request to wait for a permit from the given limiter before
+    /// sending it, using the given timer implementation.
+    #[cfg(all(feature = "std", feature = "backoff"))]
+    fn rate_limit_with_timer<L, T>(self, limiter: L, timer: T) -> RateLimited<Self, L, T>
+    where
+        Self: Sized,
+        L: RateLimiter,
+        T: Timer,
+    {
+        RateLimited::new(self, limiter, timer)
+    }
+}
+
+impl<R> RequestExt for R where R: BaseRequest + ?Sized {}
+
+/// An extension trait for [`OneshotRequest`]s, which provides the
+/// combinators depending on the type of clients.
+pub trait OneshotRequestExt<C>: OneshotRequest<C> {
+    /// Wrap this request to be sent many times, by sending its clones.
+    fn repeat(self) -> Repeat<Self>
+    where
+        Self: Clone,
+    {
+        Repeat::from(self)
+    }
+
+    /// Send this request to the given client, and block the current thread
+    /// until its response is completed.
+    ///
+    /// It doesn't need any async runtime, unless the response of the client
+    /// requires it.
+    #[cfg(feature = "blocking")]
+    fn send_blocking(self, client: C) -> Result<Self::Ok, Self::Error>
+    where
+        Self: Sized,
+    {
+        futures::executor::block_on(self.send_once(client).into_future())
+    }
+}
+
+impl<R, C> OneshotRequestExt<C> for R where R: OneshotRequest<C> + ?Sized {}
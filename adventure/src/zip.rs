@@ -0,0 +1,90 @@
+//! An adaptor to send two requests concurrently.
+use core::pin::Pin;
+
+use futures::future::{self, TryJoin};
+use pin_utils::unsafe_pinned;
+
+#[cfg(feature = "alloc")]
+use crate::extensions::Extensions;
+use crate::oneshot::OneshotRequest;
+use crate::request::{BaseRequest, Request};
+
+/// Returns a request to send both of the given requests concurrently to the
+/// same client, which results in a pair of their successful values.
+///
+/// Its response fails as soon as either of them has failed.
+pub fn zip<A, B>(a: A, b: B) -> Zip<A, B>
+where
+    A: BaseRequest,
+    B: BaseRequest<Error = A::Error>,
+{
+    Zip { a, b }
+}
+
+/// Request for [`zip`].
+///
+/// The extensions of the first request are used for this request.
+#[derive(Clone)]
+pub struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Zip<A, B> {
+    unsafe_pinned!(a: A);
+    unsafe_pinned!(b: B);
+
+    /// Consumes this adaptor, returning the underlying requests.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> BaseRequest for Zip<A, B>
+where
+    A: BaseRequest,
+    B: BaseRequest<Error = A::Error>,
+{
+    type Ok = (A::Ok, B::Ok);
+    type Error = A::Error;
+
+    #[cfg(feature = "alloc")]
+    fn extensions(&self) -> Option<&Extensions> {
+        self.a.extensions()
+    }
+}
+
+impl<A, B, C> OneshotRequest<C> for Zip<A, B>
+where
+    A: OneshotRequest<C>,
+    B: OneshotRequest<C, Error = A::Error>,
+    C: Clone,
+{
+    type Response = TryJoin<A::Response, B::Response>;
+
+    fn send_once(self, client: C) -> Self::Response {
+        let a = self.a.send_once(client.clone());
+        future::try_join(a, self.b.send_once(client))
+    }
+}
+
+impl<A, B, C> Request<C> for Zip<A, B>
+where
+    A: Request<C>,
+    B: Request<C, Error = A::Error>,
+    C: Clone,
+{
+    type Response = TryJoin<A::Response, B::Response>;
+
+    fn send(mut self: Pin<&mut Self>, client: C) -> Self::Response {
+        let a = self.as_mut().a().send(client.clone());
+        future::try_join(a, self.b().send(client))
+    }
+}
+
+impl<A, B> Unpin for Zip<A, B>
+where
+    A: Unpin,
+    B: Unpin,
+{
+}
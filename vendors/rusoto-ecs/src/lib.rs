@@ -20,8 +20,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use adventure::{
-    response::Future01Response, BaseRequest, OneshotRequest, PagedRequest, Request,
-    RetriableRequest,
+    cache::debug_key, response::Future01Response, BaseRequest, IdempotentRequest, OneshotRequest,
+    PagedRequest, Request, RequestKey, RetriableRequest,
 };
 use rusoto_core::{RusotoError, RusotoFuture};
 use rusoto_ecs::*;
@@ -29,6 +29,10 @@ use rusoto_ecs::*;
 pub type RusotoResponse<T, E> = Future01Response<RusotoFuture<T, E>>;
 
 /// A wrapper for various type of requests in [`rusoto_ecs`].
+///
+/// Read-only requests implement [`RequestKey`], keyed by their `Debug`
+/// representations with [`debug_key`], because Rusoto requests can't be
+/// hashed.
 #[derive(Clone, Debug)]
 pub struct AwsEcs<T> {
     inner: T,
@@ -156,6 +160,22 @@ macro_rules! impl_adventure {
 
         impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
     };
+    (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; idempotent; $($rest:tt)*) => {
+        impl IdempotentRequest for $wrapper<$name> {}
+
+        impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
+    };
+    (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; key; $($rest:tt)*) => {
+        impl RequestKey for $wrapper<$name> {
+            type Key = String;
+
+            fn key(&self) -> String {
+                debug_key(&self.inner)
+            }
+        }
+
+        impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
+    };
     (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; advance: $token:ident; $($rest:tt)*) => {
         impl PagedRequest for $wrapper<$name> {
             fn advance(&mut self, response: &Self::Ok) -> bool {
@@ -236,6 +256,8 @@ impl_adventure! {
         type Error = DescribeClustersError;
         retry: Server;
         send: describe_clusters;
+        idempotent;
+        key;
     }
 
     impl DescribeContainerInstancesRequest {
@@ -243,6 +265,8 @@ impl_adventure! {
         type Error = DescribeContainerInstancesError;
         retry: Server;
         send: describe_container_instances;
+        idempotent;
+        key;
     }
 
     impl DescribeServicesRequest {
@@ -250,6 +274,8 @@ impl_adventure! {
         type Error = DescribeServicesError;
         retry: Server;
         send: describe_services;
+        idempotent;
+        key;
     }
 
     impl DescribeTaskDefinitionRequest {
@@ -257,6 +283,8 @@ impl_adventure! {
         type Error = DescribeTaskDefinitionError;
         retry: Server;
         send: describe_task_definition;
+        idempotent;
+        key;
     }
 
     impl DescribeTasksRequest {
@@ -264,6 +292,8 @@ impl_adventure! {
         type Error = DescribeTasksError;
         retry: Server;
         send: describe_tasks;
+        idempotent;
+        key;
     }
 
     impl DiscoverPollEndpointRequest {
@@ -278,6 +308,8 @@ impl_adventure! {
         type Error = ListAccountSettingsError;
         retry: Server;
         send: list_account_settings;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -285,6 +317,8 @@ impl_adventure! {
         type Ok = ListAttributesResponse;
         type Error = ListAttributesError;
         send: list_attributes;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -293,6 +327,8 @@ impl_adventure! {
         type Error = ListClustersError;
         retry: Server;
         send: list_clusters;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -301,6 +337,8 @@ impl_adventure! {
         type Error = ListContainerInstancesError;
         retry: Server;
         send: list_container_instances;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -309,6 +347,8 @@ impl_adventure! {
         type Error = ListServicesError;
         retry: Server;
         send: list_services;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -317,6 +357,8 @@ impl_adventure! {
         type Error = ListTagsForResourceError;
         retry: Server;
         send: list_tags_for_resource;
+        idempotent;
+        key;
     }
 
     impl ListTaskDefinitionFamiliesRequest {
@@ -324,6 +366,8 @@ impl_adventure! {
         type Error = ListTaskDefinitionFamiliesError;
         retry: Server;
         send: list_task_definition_families;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -332,6 +376,8 @@ impl_adventure! {
         type Error = ListTaskDefinitionsError;
         retry: Server;
         send: list_task_definitions;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -340,6 +386,8 @@ impl_adventure! {
         type Error = ListTasksError;
         retry: Server;
         send: list_tasks;
+        idempotent;
+        key;
         advance: next_token;
     }
 
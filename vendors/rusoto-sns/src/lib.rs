@@ -13,8 +13,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use adventure::{
-    response::Future01Response, BaseRequest, OneshotRequest, PagedRequest, Request,
-    RetriableRequest,
+    cache::debug_key, response::Future01Response, BaseRequest, IdempotentRequest, OneshotRequest,
+    PagedRequest, Request, RequestKey, RetriableRequest,
 };
 use rusoto_core::{RusotoError, RusotoFuture};
 use rusoto_sns::*;
@@ -22,6 +22,10 @@ use rusoto_sns::*;
 pub type RusotoResponse<T, E> = Future01Response<RusotoFuture<T, E>>;
 
 /// A wrapper for various type of requests in [`rusoto_sns`].
+///
+/// Read-only requests implement [`RequestKey`], keyed by their `Debug`
+/// representations with [`debug_key`], because Rusoto requests can't be
+/// hashed.
 #[derive(Clone, Debug)]
 pub struct AwsSns<T> {
     inner: T,
@@ -149,6 +153,22 @@ macro_rules! impl_adventure {
 
         impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
     };
+    (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; idempotent; $($rest:tt)*) => {
+        impl IdempotentRequest for $wrapper<$name> {}
+
+        impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
+    };
+    (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; key; $($rest:tt)*) => {
+        impl RequestKey for $wrapper<$name> {
+            type Key = String;
+
+            fn key(&self) -> String {
+                debug_key(&self.inner)
+            }
+        }
+
+        impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
+    };
     (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; advance: $token:ident; $($rest:tt)*) => {
         impl PagedRequest for $wrapper<$name> {
             fn advance(&mut self, response: &Self::Ok) -> bool {
@@ -181,6 +201,8 @@ impl_adventure! {
         type Error = CheckIfPhoneNumberIsOptedOutError;
         retry: Server;
         send: check_if_phone_number_is_opted_out;
+        idempotent;
+        key;
     }
 
     impl ConfirmSubscriptionInput {
@@ -237,6 +259,8 @@ impl_adventure! {
         type Error = GetEndpointAttributesError;
         retry: Server;
         send: get_endpoint_attributes;
+        idempotent;
+        key;
     }
 
     impl GetPlatformApplicationAttributesInput {
@@ -244,6 +268,8 @@ impl_adventure! {
         type Error = GetPlatformApplicationAttributesError;
         retry: Server;
         send: get_platform_application_attributes;
+        idempotent;
+        key;
     }
 
     impl GetSMSAttributesInput {
@@ -251,6 +277,8 @@ impl_adventure! {
         type Error = GetSMSAttributesError;
         retry: Server;
         send: get_sms_attributes;
+        idempotent;
+        key;
     }
 
     impl GetSubscriptionAttributesInput {
@@ -258,6 +286,8 @@ impl_adventure! {
         type Error = GetSubscriptionAttributesError;
         retry: Server;
         send: get_subscription_attributes;
+        idempotent;
+        key;
     }
 
     impl GetTopicAttributesInput {
@@ -265,6 +295,8 @@ impl_adventure! {
         type Error = GetTopicAttributesError;
         retry: Server;
         send: get_topic_attributes;
+        idempotent;
+        key;
     }
 
     impl ListEndpointsByPlatformApplicationInput {
@@ -272,6 +304,8 @@ impl_adventure! {
         type Error = ListEndpointsByPlatformApplicationError;
         retry: Server;
         send: list_endpoints_by_platform_application;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -280,6 +314,8 @@ impl_adventure! {
         type Error = ListPhoneNumbersOptedOutError;
         retry: Server;
         send: list_phone_numbers_opted_out;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -288,6 +324,8 @@ impl_adventure! {
         type Error = ListPlatformApplicationsError;
         retry: Server;
         send: list_platform_applications;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -296,6 +334,8 @@ impl_adventure! {
         type Error = ListSubscriptionsError;
         retry: Server;
         send: list_subscriptions;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -304,6 +344,8 @@ impl_adventure! {
         type Error = ListSubscriptionsByTopicError;
         retry: Server;
         send: list_subscriptions_by_topic;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -312,6 +354,8 @@ impl_adventure! {
         type Error = ListTagsForResourceError;
         retry: Server;
         send: list_tags_for_resource;
+        idempotent;
+        key;
     }
 
     impl ListTopicsInput {
@@ -319,6 +363,8 @@ impl_adventure! {
         type Error = ListTopicsError;
         retry: Server;
         send: list_topics;
+        idempotent;
+        key;
         advance: next_token;
     }
 
@@ -13,8 +13,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use adventure::{
-    response::Future01Response, BaseRequest, OneshotRequest, PagedRequest, Request,
-    RetriableRequest,
+    cache::debug_key, response::Future01Response, BaseRequest, IdempotentRequest, OneshotRequest,
+    PagedRequest, Request, RequestKey, RetriableRequest,
 };
 use futures::Future;
 use rusoto_core::{RusotoError, RusotoFuture};
@@ -23,6 +23,10 @@ use rusoto_sqs::*;
 pub type RusotoResponse<T, E> = Future01Response<RusotoFuture<T, E>>;
 
 /// A wrapper for various type of requests in [`rusoto_sqs`].
+///
+/// Read-only requests implement [`RequestKey`], keyed by their `Debug`
+/// representations with [`debug_key`], because Rusoto requests can't be
+/// hashed.
 #[derive(Clone, Debug)]
 pub struct AwsSqs<T> {
     inner: T,
@@ -150,6 +154,22 @@ macro_rules! impl_adventure {
 
         impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
     };
+    (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; idempotent; $($rest:tt)*) => {
+        impl IdempotentRequest for $wrapper<$name> {}
+
+        impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
+    };
+    (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; key; $($rest:tt)*) => {
+        impl RequestKey for $wrapper<$name> {
+            type Key = String;
+
+            fn key(&self) -> String {
+                debug_key(&self.inner)
+            }
+        }
+
+        impl_adventure!(@@ $wrapper, $name, $client, $error; $($rest)*);
+    };
     (@@ $wrapper:ident, $name:ident, $client:ident, $error:ident; advance: $token:ident; $($rest:tt)*) => {
         impl PagedRequest for $wrapper<$name> {
             fn advance(&mut self, response: &Self::Ok) -> bool {
@@ -224,6 +244,8 @@ impl_adventure! {
         type Error = GetQueueAttributesError;
         retry: Server;
         send: get_queue_attributes;
+        idempotent;
+        key;
     }
 
     impl GetQueueUrlRequest {
@@ -231,6 +253,8 @@ impl_adventure! {
         type Error = GetQueueUrlError;
         retry: Server;
         send: get_queue_url;
+        idempotent;
+        key;
     }
 
     impl ListDeadLetterSourceQueuesRequest {
@@ -238,6 +262,8 @@ impl_adventure! {
         type Error = ListDeadLetterSourceQueuesError;
         retry: Server;
         send: list_dead_letter_source_queues;
+        idempotent;
+        key;
     }
 
     impl ListQueueTagsRequest {
@@ -245,6 +271,8 @@ impl_adventure! {
         type Error = ListQueueTagsError;
         retry: Server;
         send: list_queue_tags;
+        idempotent;
+        key;
     }
 
     impl ListQueuesRequest {
@@ -252,6 +280,8 @@ impl_adventure! {
         type Error = ListQueuesError;
         retry: Server;
         send: list_queues;
+        idempotent;
+        key;
     }
 
     impl PurgeQueueRequest {